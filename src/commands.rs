@@ -2,6 +2,8 @@ mod app;
 mod bucket;
 mod cache;
 mod checkup;
+mod complete;
+mod completions;
 mod credits;
 mod debug;
 mod depends;
@@ -108,13 +110,7 @@ pub trait CommandRunner: Command {
 
 impl<T: Command> CommandRunner for T {}
 
-// TODO: Replace strip macro with a custom enum
-// Use match to ensure all variants are covered
-// This will allow more flexibility with hooks in future
-// as sfsu invocation deviates from scoop's
-#[derive(Debug, Clone, Subcommand, quork::macros::Strip)]
-#[stripped(ident = CommandHooks)]
-#[stripped_meta(derive(Debug, Copy, Clone, quork::macros::ListVariants, PartialEq, Eq))]
+#[derive(Debug, Clone, Subcommand)]
 pub enum Commands {
     App(app::Args),
     #[cfg(not(feature = "v2"))]
@@ -128,7 +124,6 @@ pub enum Commands {
     #[cfg(not(feature = "v2"))]
     List(app::list::Args),
 
-    #[stripped(ignore)]
     Hook(hook::Args),
 
     Search(search::Args),
@@ -141,7 +136,6 @@ pub enum Commands {
     Outdated(outdated::Args),
     Depends(depends::Args),
     Status(status::Args),
-    #[cfg_attr(not(feature = "v2"), stripped(ignore))]
     Update(update::Args),
     Export(export::Args),
     Checkup(checkup::Args),
@@ -149,9 +143,10 @@ pub enum Commands {
     Cache(cache::Args),
     #[clap(alias = "virustotal")]
     Scan(virustotal::Args),
-    #[stripped(ignore)]
     Credits(credits::Args),
-    #[stripped(ignore)]
+    Completions(completions::Args),
+    #[clap(hide = true)]
+    Complete(complete::Args),
     #[cfg(debug_assertions)]
     Debug(debug::Args),
 }
@@ -190,13 +185,52 @@ impl Runnable for Commands {
             Commands::Cache(args) => args.run(ctx).await,
             Commands::Scan(args) => args.run(ctx).await,
             Commands::Credits(args) => args.run(ctx).await,
+            Commands::Completions(args) => args.run(ctx).await,
+            Commands::Complete(args) => args.run(ctx).await,
             #[cfg(debug_assertions)]
             Commands::Debug(args) => args.run(ctx).await,
         }
     }
 }
 
+/// The commands that can be hooked into Scoop
+///
+/// This is kept separate from [`Commands`], as not every command makes sense as a hook,
+/// and hook names follow Scoop's commands rather than sfsu's
+#[derive(Debug, Copy, Clone, quork::macros::ListVariants, PartialEq, Eq)]
+pub enum CommandHooks {
+    App,
+    #[cfg(not(feature = "v2"))]
+    Cat,
+    #[cfg(all(feature = "download", not(feature = "v2")))]
+    Download,
+    #[cfg(not(feature = "v2"))]
+    Home,
+    #[cfg(not(feature = "v2"))]
+    Info,
+    #[cfg(not(feature = "v2"))]
+    List,
+    Search,
+    #[cfg(not(feature = "v2"))]
+    UnusedBuckets,
+    Bucket,
+    #[cfg(not(feature = "v2"))]
+    Describe,
+    #[cfg(not(feature = "v2"))]
+    Outdated,
+    Depends,
+    Status,
+    Export,
+    Checkup,
+    #[cfg(feature = "download")]
+    Cache,
+    Scan,
+    #[cfg(feature = "v2")]
+    Update,
+}
+
 impl CommandHooks {
+    /// The sfsu command the hook runs
     pub const fn command<'a>(self) -> &'a str {
         match self {
             CommandHooks::App => "app",
@@ -222,6 +256,7 @@ impl CommandHooks {
             CommandHooks::Status => "status",
             CommandHooks::Export => "export",
             CommandHooks::Checkup => "checkup",
+            #[cfg(feature = "download")]
             CommandHooks::Cache => "cache",
             CommandHooks::Scan => "scan",
             #[cfg(feature = "v2")]
@@ -229,6 +264,7 @@ impl CommandHooks {
         }
     }
 
+    /// The Scoop command the hook replaces
     pub const fn hook<'a>(self) -> &'a str {
         match self {
             CommandHooks::App => "app",
@@ -254,6 +290,7 @@ impl CommandHooks {
             CommandHooks::Status => "status",
             CommandHooks::Export => "export",
             CommandHooks::Checkup => "checkup",
+            #[cfg(feature = "download")]
             CommandHooks::Cache => "cache",
             CommandHooks::Scan => "virustotal",
             #[cfg(feature = "v2")]
@@ -262,37 +299,28 @@ impl CommandHooks {
     }
 }
 
-impl From<String> for CommandHooks {
-    fn from(string: String) -> Self {
-        match string.as_str() {
-            "app" => CommandHooks::App,
-            #[cfg(not(feature = "v2"))]
-            "cat" => CommandHooks::Cat,
-            #[cfg(all(feature = "download", not(feature = "v2")))]
-            "download" => CommandHooks::Download,
-            #[cfg(not(feature = "v2"))]
-            "home" => CommandHooks::Home,
-            #[cfg(not(feature = "v2"))]
-            "info" => CommandHooks::Info,
-            #[cfg(not(feature = "v2"))]
-            "list" => CommandHooks::List,
-            "search" => CommandHooks::Search,
-            #[cfg(not(feature = "v2"))]
-            "unused-buckets" => CommandHooks::UnusedBuckets,
-            "bucket" => CommandHooks::Bucket,
-            #[cfg(not(feature = "v2"))]
-            "describe" => CommandHooks::Describe,
-            #[cfg(not(feature = "v2"))]
-            "outdated" => CommandHooks::Outdated,
-            "depends" => CommandHooks::Depends,
-            "status" => CommandHooks::Status,
-            "export" => CommandHooks::Export,
-            "checkup" => CommandHooks::Checkup,
-            "cache" => CommandHooks::Cache,
-            "virustotal" => CommandHooks::Scan,
-            #[cfg(feature = "v2")]
-            "update" => CommandHooks::Update,
-            _ => panic!("Invalid command name: {string}"),
-        }
+impl std::str::FromStr for CommandHooks {
+    type Err = InvalidHook;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|variant| variant.hook() == s)
+            .ok_or_else(|| InvalidHook(s.to_string()))
     }
 }
+
+#[derive(Debug, Clone)]
+/// A hook name that doesn't match any command
+pub struct InvalidHook(String);
+
+impl std::fmt::Display for InvalidHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no hook named \"{}\". Valid hooks are: ", self.0)?;
+
+        let hooks = CommandHooks::VARIANTS.map(CommandHooks::hook);
+        write!(f, "{}", hooks.join(", "))
+    }
+}
+
+impl std::error::Error for InvalidHook {}
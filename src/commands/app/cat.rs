@@ -8,7 +8,7 @@ use crate::{abandon, COLOR_ENABLED};
 #[derive(Debug, Clone, Parser)]
 /// Show content of specified manifest
 pub struct Args {
-    #[clap(help = "The manifest to display")]
+    #[clap(value_name = "PACKAGE", help = "The manifest to display")]
     package: package::Reference,
 }
 
@@ -25,6 +25,7 @@ use crate::{
     abandon,
     models::status::Info,
     output::colours::{bright_red, eprintln_yellow},
+    wrappers::version::Change,
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -33,12 +34,19 @@ pub struct Args {
     #[clap(short = 'H', long, help = "Disable hash validation")]
     no_hash_check: bool,
 
-    #[clap(help = "The packages to download")]
+    #[clap(value_name = "PACKAGES", help = "The packages to download")]
     packages: Vec<package::Reference>,
 
     #[clap(long, help = "Download new versions of all outdated apps")]
     outdated: bool,
 
+    #[clap(
+        long,
+        help = "With --outdated, download the newest version from any bucket, not just the bucket each app was installed from",
+        requires = "outdated"
+    )]
+    all_buckets: bool,
+
     #[clap(from_global)]
     arch: Architecture,
 }
@@ -49,7 +57,7 @@ impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let packages = if self.packages.is_empty() {
             if self.outdated {
-                list_outdated(ctx)?
+                list_outdated(ctx, self.all_buckets)?
             } else {
                 abandon!("No packages provided")
             }
@@ -147,19 +155,28 @@ impl super::Command for Args {
     }
 }
 
-fn list_outdated(ctx: &impl ScoopContext) -> Result<Vec<package::Reference>, anyhow::Error> {
+fn list_outdated(
+    ctx: &impl ScoopContext,
+    all_buckets: bool,
+) -> Result<Vec<package::Reference>, anyhow::Error> {
     let apps = install::Manifest::list_all_unchecked(ctx)?;
 
+    let all_buckets = if all_buckets {
+        Bucket::list_all(ctx)?
+    } else {
+        vec![]
+    };
+
     Ok(apps
         .par_iter()
         .flat_map(|app| -> anyhow::Result<Info> {
             if let Some(bucket) = &app.bucket {
                 let local_manifest = app.get_manifest(ctx)?;
-                // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
+                let bucket_name = bucket;
                 let bucket = Bucket::from_name(ctx, bucket)?;
 
                 match Info::from_manifests(ctx, &local_manifest, &bucket) {
-                    Ok(info) => Ok(info),
+                    Ok(info) => Ok(info.with_alternative(&all_buckets, bucket_name)),
                     Err(err) => {
                         error!(
                             "Failed to get status for {}: {:?}",
@@ -174,7 +191,31 @@ fn list_outdated(ctx: &impl ScoopContext) -> Result<Vec<package::Reference>, any
                 anyhow::bail!("no bucket specified")
             }
         })
-        .filter(|app| app.current != app.available)
-        .map(|app| manifest::Reference::Name(app.name).into_package_ref())
+        .filter_map(|app| {
+            if let (Some(bucket), Some(version)) = (app.alternative_bucket, app.alternative_version)
+            {
+                eprintln!(
+                    "{}: {} -> {version} (upgrade, from {bucket} bucket)",
+                    app.name, app.current
+                );
+
+                return format!("{bucket}/{}", app.name).parse().ok();
+            }
+
+            let change = app.change?;
+
+            eprintln!(
+                "{}: {} -> {} ({change})",
+                app.name, app.current, app.available
+            );
+
+            // Never download older versions, i.e if a bucket was rolled back
+            if change == Change::Downgrade {
+                eprintln_yellow!("Skipping {}, as the available version is older", app.name);
+                return None;
+            }
+
+            Some(manifest::Reference::Name(app.name).into_package_ref())
+        })
         .collect::<Vec<_>>())
 }
@@ -6,7 +6,7 @@ use crate::abandon;
 #[derive(Debug, Clone, Parser)]
 /// Opens the app homepage
 pub struct Args {
-    #[clap(help = "The package to open the homepage for")]
+    #[clap(value_name = "PACKAGE", help = "The package to open the homepage for")]
     package: package::Reference,
 }
 
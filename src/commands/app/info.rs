@@ -15,7 +15,7 @@ use crate::{
     abandon,
     models::info::Package,
     output::structured::vertical::VTable,
-    wrappers::{bool::NicerBool, time::NicerTime},
+    wrappers::{bool::NicerBool, time::NicerTime, version::Version},
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -23,7 +23,7 @@ use crate::{
 // TODO: Pass architecture
 /// Display information about a package
 pub struct Args {
-    #[clap(help = "The package to get info from")]
+    #[clap(value_name = "PACKAGE", help = "The package to get info from")]
     package: package::Reference,
 
     #[cfg(not(feature = "v2"))]
@@ -74,10 +74,8 @@ impl super::Command for Args {
             let latest = manifests
                 .into_iter()
                 .max_by(|a_manifest, b_manifest| {
-                    semver::Version::try_from(&a_manifest.version)
-                        .and_then(|a_version| {
-                            Ok(a_version.cmp(&semver::Version::try_from(&b_manifest.version)?))
-                        })
+                    Version::new(&a_manifest.version.to_string())
+                        .partial_cmp(&Version::new(&b_manifest.version.to_string()))
                         .unwrap_or(std::cmp::Ordering::Equal)
                 }).expect("something went terribly wrong (no manifests found even though we just checked for manifests)");
 
@@ -3,6 +3,11 @@ use rayon::prelude::*;
 
 use sprinkles::contexts::ScoopContext;
 
+#[cfg(not(feature = "v2"))]
+use crate::{
+    calm_panic::CalmUnwrap,
+    pattern::{Pattern, Syntax, SyntaxArgs},
+};
 use crate::{models::min::Info, output::structured::Structured};
 
 #[derive(Debug, Clone, Parser)]
@@ -14,6 +19,10 @@ pub struct Args {
     )]
     pattern: Option<String>,
 
+    #[cfg(not(feature = "v2"))]
+    #[clap(flatten)]
+    syntax: SyntaxArgs,
+
     #[clap(short, long, help = "The bucket to exclusively list packages in")]
     bucket: Option<String>,
 
@@ -40,6 +49,14 @@ impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let mut outputs = Info::list_installed(ctx, self.bucket.as_ref())?;
 
+        #[cfg(not(feature = "v2"))]
+        if let Some(ref pattern) = self.pattern {
+            let pattern = Pattern::new(pattern, self.syntax.syntax_or(Syntax::Regex), false)
+                .calm_expect("Invalid pattern provided");
+
+            outputs.retain(|info| pattern.is_match(&info.name));
+        }
+
         outputs.par_sort_by(|a, b| match self.sort_by {
             SortBy::Name => a.name.cmp(&b.name),
             SortBy::Version => a.version.cmp(&b.version),
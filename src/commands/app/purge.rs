@@ -17,7 +17,7 @@ use crate::output::colours::{eprintln_yellow, yellow};
 #[derive(Debug, Clone, Parser)]
 /// Purge package's persist folder
 pub struct Args {
-    #[clap(help = "The package to purge")]
+    #[clap(value_name = "APPS", help = "The package to purge")]
     apps: Vec<package::Reference>,
 
     #[clap(from_global)]
@@ -3,6 +3,7 @@ pub mod known;
 pub mod list;
 pub mod outdated;
 pub mod remove;
+pub mod rollback;
 pub mod unused;
 
 use clap::{Parser, Subcommand};
@@ -18,6 +19,7 @@ pub enum Commands {
     Remove(remove::Args),
     List(list::Args),
     Known(known::Args),
+    Rollback(rollback::Args),
     Unused(unused::Args),
     #[cfg(not(feature = "v2"))]
     Outdated(outdated::Args),
@@ -33,6 +35,7 @@ impl Runnable for Commands {
             Commands::Remove(args) => args.run(ctx).await,
             Commands::List(args) => args.run(ctx).await,
             Commands::Known(args) => args.run(ctx).await,
+            Commands::Rollback(args) => args.run(ctx).await,
             Commands::Unused(args) => args.run(ctx).await,
             #[cfg(not(feature = "v2"))]
             Commands::Outdated(args) => args.run(ctx).await,
@@ -20,12 +20,20 @@ struct BucketInfo {
     source: String,
     updated: NicerTime<FixedOffset>,
     manifests: usize,
+    pinned: Option<String>,
 }
 
 impl BucketInfo {
-    fn collect(bucket: &Bucket) -> anyhow::Result<Self> {
+    fn collect(ctx: &impl ScoopContext, bucket: &Bucket) -> anyhow::Result<Self> {
         let manifests = bucket.manifests()?;
 
+        // A bucket that is behind its remote after an update has been rolled back
+        let pinned = crate::git::behind_upstream(&ctx.buckets_path().join(bucket.name().as_ref()))
+            .ok()
+            .flatten()
+            .filter(|behind| *behind > 0)
+            .map(|behind| format!("{behind} commits behind"));
+
         let updated_time = {
             let repo = bucket.open_repo()?;
             let latest_commit = repo.latest_commit()?;
@@ -39,6 +47,7 @@ impl BucketInfo {
             source: bucket.source()?.to_string(),
             updated: updated_time.into(),
             manifests,
+            pinned,
         })
     }
 }
@@ -50,7 +59,7 @@ impl super::Command for Args {
         let buckets = {
             let mut buckets = buckets
                 .par_iter()
-                .map(BucketInfo::collect)
+                .map(|bucket| BucketInfo::collect(ctx, bucket))
                 .collect::<Result<Vec<_>, _>>()?;
 
             buckets.sort_by(|a, b| a.name.cmp(&b.name));
@@ -7,7 +7,7 @@ use crate::{abandon, output::colours::yellow};
 #[derive(Debug, Clone, Parser)]
 /// Remove a bucket
 pub struct Args {
-    #[clap(help = "The name of the bucket to delete")]
+    #[clap(value_name = "BUCKET", help = "The name of the bucket to delete")]
     name: String,
 
     #[clap(from_global)]
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use dialoguer::Confirm;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    abandon,
+    journal::{self, Journal},
+    output::colours::eprintln_green,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Roll a bucket back to the commit it was at before it was last updated
+pub struct Args {
+    #[clap(
+        value_name = "BUCKET",
+        help = "The name of the bucket to roll back. Rolls back Scoop and every bucket changed by the last update if not provided"
+    )]
+    name: Option<String>,
+
+    #[clap(
+        long,
+        requires = "name",
+        help = "The commit to roll back to, instead of the one recorded before the last update"
+    )]
+    to: Option<String>,
+
+    #[clap(from_global)]
+    assume_yes: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let journal = Journal::load(ctx)?;
+
+        let targets: Vec<(String, String)> = if let Some(name) = &self.name {
+            let commit = match &self.to {
+                Some(commit) => commit.clone(),
+                None => match journal.last_commit(name) {
+                    Some(commit) => commit.to_string(),
+                    None => abandon!(
+                        "No update recorded for bucket \"{name}\". Pass a commit with `--to` instead"
+                    ),
+                },
+            };
+
+            vec![(name.clone(), commit)]
+        } else {
+            match journal.last() {
+                Some(update) => update.commits.clone().into_iter().collect(),
+                None => abandon!("No updates have been recorded yet"),
+            }
+        };
+
+        for (name, commit) in targets {
+            let path = Self::path(ctx, &name);
+
+            if !path.exists() {
+                abandon!("Bucket \"{name}\" is not installed");
+            }
+
+            let response = self.assume_yes
+                || Confirm::new()
+                    .with_prompt(format!("Roll back \"{name}\" to {commit}?"))
+                    .default(true)
+                    .interact()?;
+
+            if response {
+                let commit = crate::git::reset_hard(&path, &commit)?;
+
+                eprintln_green!("Rolled back \"{name}\" to {commit}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    fn path(ctx: &impl ScoopContext, name: &str) -> PathBuf {
+        if name == journal::SCOOP {
+            ctx.apps_path().join("scoop").join("current")
+        } else {
+            ctx.buckets_path().join(name)
+        }
+    }
+}
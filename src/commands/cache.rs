@@ -2,7 +2,6 @@ use std::{os::windows::fs::MetadataExt, path::PathBuf};
 
 use anyhow::Context;
 use clap::{Parser, Subcommand};
-use regex::Regex;
 use serde::Serialize;
 use sprinkles::{config, contexts::ScoopContext};
 use tokio::task::JoinSet;
@@ -10,7 +9,12 @@ use tokio::task::JoinSet;
 mod list;
 mod remove;
 
-use crate::{abandon, commands::CommandRunner, wrappers::sizes::Size};
+use crate::{
+    abandon,
+    commands::CommandRunner,
+    pattern::{Pattern, Syntax, SyntaxArgs},
+    wrappers::sizes::Size,
+};
 
 use super::Runnable;
 
@@ -28,13 +32,14 @@ impl CacheEntry {
     pub async fn match_paths(
         ctx: &impl ScoopContext,
         patterns: &[String],
+        syntax: &SyntaxArgs,
     ) -> anyhow::Result<Vec<Self>> {
         let cache_path = ctx.cache_path();
 
         let patterns = patterns
             .iter()
-            .filter_map(|pattern| Regex::new(&format!("^{pattern}#")).ok())
-            .collect::<Vec<_>>();
+            .map(|pattern| Pattern::whole(pattern, syntax.syntax_or(Syntax::Glob), false))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let mut set = JoinSet::new();
         let mut dir = tokio::fs::read_dir(cache_path).await?;
@@ -43,7 +48,12 @@ impl CacheEntry {
             let file_name = entry.file_name();
             let file_name = file_name.to_string_lossy();
 
-            if !patterns.iter().any(|pattern| pattern.is_match(&file_name)) {
+            // Cache entries are named `<app>#<version>#<url>`
+            let Some((app, _)) = file_name.split_once('#') else {
+                continue;
+            };
+
+            if !patterns.is_empty() && !patterns.iter().any(|pattern| pattern.is_match(app)) {
                 continue;
             }
 
@@ -122,11 +132,14 @@ pub struct Args {
 
     #[clap(
         global = true,
-        help = "Glob pattern(s) for apps to show cache entries for",
-        default_value = ".*?"
+        value_name = "CACHED_APPS",
+        help = "Pattern(s) for apps to show cache entries for. Defaults to all apps"
     )]
     apps: Vec<String>,
 
+    #[clap(flatten)]
+    syntax: SyntaxArgs,
+
     #[clap(from_global)]
     json: bool,
 }
@@ -139,6 +152,7 @@ impl super::Command for Args {
         let command = self.command.unwrap_or(Commands::List(list::Args {
             json: self.json,
             apps: self.apps,
+            syntax: (&self.syntax).into(),
         }));
 
         command.run(ctx).await
@@ -4,6 +4,7 @@ use sprinkles::contexts::ScoopContext;
 use crate::{
     commands::Command,
     output::{colours::eprintln_bright_yellow, structured::Structured},
+    pattern::{GlobalSyntaxArgs, SyntaxArgs},
     wrappers::sizes::Size,
 };
 
@@ -15,13 +16,17 @@ pub struct Args {
     #[clap(from_global)]
     pub apps: Vec<String>,
 
+    #[clap(flatten)]
+    pub syntax: GlobalSyntaxArgs,
+
     #[clap(from_global)]
     pub json: bool,
 }
 
 impl Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let cache_entries = CacheEntry::match_paths(ctx, &self.apps).await?;
+        let cache_entries =
+            CacheEntry::match_paths(ctx, &self.apps, &SyntaxArgs::from(&self.syntax)).await?;
 
         let total_size = cache_entries
             .iter()
@@ -1,7 +1,12 @@
 use clap::Parser;
 use sprinkles::contexts::ScoopContext;
 
-use crate::{commands::Command, output::colours::eprintln_bright_yellow, wrappers::sizes::Size};
+use crate::{
+    commands::Command,
+    output::colours::eprintln_bright_yellow,
+    pattern::{GlobalSyntaxArgs, SyntaxArgs},
+    wrappers::sizes::Size,
+};
 
 use super::CacheEntry;
 
@@ -10,11 +15,15 @@ use super::CacheEntry;
 pub struct Args {
     #[clap(from_global)]
     apps: Vec<String>,
+
+    #[clap(flatten)]
+    syntax: GlobalSyntaxArgs,
 }
 
 impl Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let cache_entries = CacheEntry::match_paths(ctx, &self.apps).await?;
+        let cache_entries =
+            CacheEntry::match_paths(ctx, &self.apps, &SyntaxArgs::from(&self.syntax)).await?;
 
         let total_entires = cache_entries.len();
         let total_size = cache_entries
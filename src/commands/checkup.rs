@@ -1,93 +1,202 @@
 use clap::Parser;
 
-use itertools::Itertools;
+use dialoguer::Confirm;
+use serde::Serialize;
 use sprinkles::contexts::ScoopContext;
 
-use crate::diagnostics::{Diagnostics, LongPathsStatus};
+use crate::{
+    config::Config,
+    diagnostics::{Check, Outcome, Registry, ReportEntry, Severity, Status, System},
+    output::{
+        colours::{eprintln_green, eprintln_red},
+        structured::Structured,
+    },
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Check for common issues
 pub struct Args {
+    #[clap(
+        long,
+        help = "Fix the problems that can be fixed automatically, prompting before each fix"
+    )]
+    fix: bool,
+
+    #[clap(
+        long,
+        value_name = "CHECK",
+        value_delimiter = ',',
+        help = "Only run the checks with the given ids"
+    )]
+    only: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "CHECK",
+        value_delimiter = ',',
+        help = "Skip the checks with the given ids"
+    )]
+    skip: Vec<String>,
+
+    #[clap(long, help = "List the available checks, and their ids")]
+    list: bool,
+
     #[clap(from_global)]
     json: bool,
+
+    #[clap(from_global)]
+    assume_yes: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckInfo {
+    id: &'static str,
+    title: &'static str,
+    severity: Severity,
+}
+
+impl CheckInfo {
+    fn new(check: &dyn Check) -> Self {
+        Self {
+            id: check.id(),
+            title: check.title(),
+            severity: check.severity(),
+        }
+    }
 }
 
+/// The exit code when any check fails
+const FAILED_EXIT_CODE: i32 = 2;
+
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let diagnostics = Diagnostics::collect(ctx)?;
+        let registry = Registry::builtin(&Config::load(ctx)?);
+
+        if self.list {
+            let checks = registry.checks().map(CheckInfo::new).collect::<Vec<_>>();
+
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&checks)?);
+            } else {
+                println!("{}", Structured::new(&checks));
+            }
 
-        if self.json {
-            println!("{}", serde_json::to_string_pretty(&diagnostics)?);
             return Ok(());
         }
 
-        if diagnostics.git_installed {
-            println!("✅ Git is installed");
+        let ids = registry.checks().map(Check::id).collect::<Vec<_>>();
+        let registry = registry.filter(&self.only, &self.skip)?;
+        let provider = System::new(ctx);
+        let outcomes = registry.run(&provider);
+
+        // Fixes are applied before reporting, so the report and exit code reflect what is left to fix
+        let outcomes = if self.fix {
+            self.apply_fixes(&provider, outcomes)?
         } else {
-            println!("❌ Git is not installed");
-            println!("\tScoop relies on Git to manage itself and its buckets. sfsu does not expressly require git, but it is still recommended to install it, until sfsu can manage itself entirely without Scoop.");
-            println!("\tRun `scoop install git` to install it");
-        }
+            outcomes
+        };
+
+        // Checks left out with `--only` or `--skip` are still reported, so the report always lists every check
+        let report = ids
+            .into_iter()
+            .flat_map(
+                |id| match outcomes.iter().find(|outcome| outcome.check.id() == id) {
+                    Some(outcome) => outcome.report(),
+                    None => vec![ReportEntry::skipped(id, "Not selected to run")],
+                },
+            )
+            .collect::<Vec<_>>();
 
-        if diagnostics.main_bucket {
-            println!("✅ Main bucket is installed");
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
         } else {
-            println!("❌ Main bucket is not installed");
-            println!("\tRun `scoop bucket add main` to install it");
+            for outcome in &outcomes {
+                print_outcome(outcome);
+            }
         }
 
-        // I've disabled this because it's highly insecure to disable Windows Defender for Scoop
-        // if !sprinkles::is_elevated()? {
-        //     println!("❓ Windows Defender status could not be checked");
-        //     println!("\tRun this command as admin to check Windows Defender status");
-        // } else if diagnostics.windows_defender {
-        //     println!("✅ Windows Defender is ignoring the Scoop directory");
-        // } else {
-        //     println!("❌ Windows Defender is not ignoring the Scoop directory");
-        //     println!("\tWindows Defender may slow down or disrupt installs with realtime scanning");
-        //     println!(
-        //         "\tConsider running: `sudo Add-MpPreference -ExclusionPath '{}'`",
-        //         User::path().display()
-        //     );
-        // }
-
-        if diagnostics.windows_developer {
-            println!("✅ Windows Developer Mode is enabled");
-        } else {
-            println!("❌ Windows Developer Mode is not enabled");
-            println!("\tWindows Developer Mode is not enabled. Operations relevant to symlinks may fail without proper rights");
+        if report.iter().any(|entry| entry.status == Status::Fail) {
+            std::process::exit(FAILED_EXIT_CODE);
         }
 
-        match diagnostics.long_paths {
-            LongPathsStatus::Enabled => println!("✅ Long paths are enabled"),
-            LongPathsStatus::OldWindows => {
-                println!("❌ This version of Windows does not support long paths");
-            }
-            LongPathsStatus::Disabled => {
-                println!("❌ Long paths are disabled");
-                println!("\tRun `Set-ItemProperty 'HKLM:\\SYSTEM\\CurrentControlSet\\Control\\FileSystem' -Name 'LongPathsEnabled' -Value 1` as admin to enable it");
+        Ok(())
+    }
+}
+
+fn print_outcome(outcome: &Outcome<'_>) {
+    let check = outcome.check;
+
+    match &outcome.findings {
+        Ok(findings) if findings.is_empty() => println!("✅ {}", check.title()),
+        Ok(findings) => {
+            for finding in findings {
+                println!("{} {}", check.severity().emoji(), finding.message);
+
+                if let Some(remedy) = &finding.remedy {
+                    println!("\t{remedy}");
+                }
             }
         }
+        Err(e) => println!("❓ {} could not be checked: {e}", check.title()),
+    }
+}
 
-        if diagnostics.scoop_ntfs {
-            println!("✅ NTFS is the filesystem of the Scoop directory");
-        } else {
-            println!("❌ NTFS is not the filesystem of the Scoop directory");
-            println!("\tScoop requires an NTFS volume to work! Please point `$env:SCOOP or 'root_path' variable in '~/.config/scoop/config.json' to another Drive with NTFS filesystem");
+impl Args {
+    /// Prompt to fix each fixable finding, and run the checks with fixed findings again
+    fn apply_fixes<'a>(
+        &self,
+        provider: &System,
+        outcomes: Vec<Outcome<'a>>,
+    ) -> anyhow::Result<Vec<Outcome<'a>>> {
+        outcomes
+            .into_iter()
+            .map(|outcome| {
+                if !self.fix_outcome(provider, &outcome)? {
+                    return Ok(outcome);
+                }
+
+                Ok(Outcome {
+                    check: outcome.check,
+                    findings: outcome.check.run(provider),
+                })
+            })
+            .collect()
+    }
+
+    /// Prompt to fix each of the outcome's findings, returning whether any were fixed
+    fn fix_outcome(&self, provider: &System, outcome: &Outcome<'_>) -> anyhow::Result<bool> {
+        let check = outcome.check;
+
+        let Ok(findings) = &outcome.findings else {
+            return Ok(false);
+        };
+
+        if !check.fixable() {
+            return Ok(false);
         }
 
-        for helper in diagnostics.missing_helpers {
-            println!("❌ Missing helper: {}", helper.name);
-            println!(
-                "\tInstall it with: {}",
-                helper
-                    .packages
-                    .iter()
-                    .map(|pkg| format!("`scoop install {pkg}`"))
-                    .join(" or ")
-            );
+        let mut fixed = false;
+
+        for finding in findings {
+            let response = self.assume_yes
+                || Confirm::new()
+                    .with_prompt(format!("Fix \"{}\"?", finding.message))
+                    .default(false)
+                    .interact()?;
+
+            if !response {
+                continue;
+            }
+
+            match check.fix(provider, finding) {
+                Ok(()) => {
+                    fixed = true;
+                    eprintln_green!("Fixed: {}", finding.message);
+                }
+                Err(e) => eprintln_red!("Failed to fix \"{}\": {e}", finding.message),
+            }
         }
 
-        Ok(())
+        Ok(fixed)
     }
 }
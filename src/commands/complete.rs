@@ -0,0 +1,27 @@
+use clap::Parser;
+use sprinkles::contexts::ScoopContext;
+
+use crate::completions::Kind;
+
+#[derive(Debug, Clone, Parser)]
+/// List completion candidates. Called by the scripts generated by `sfsu completions`
+pub struct Args {
+    #[clap(help = "The kind of values to complete")]
+    kind: Kind,
+
+    #[clap(
+        help = "Only list values starting with this prefix",
+        default_value = ""
+    )]
+    prefix: String,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
+        for candidate in self.kind.candidates(ctx, &self.prefix)? {
+            println!("{candidate}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use clap::{CommandFactory, Parser};
+use sprinkles::contexts::ScoopContext;
+
+use crate::shell::Shell;
+
+#[derive(Debug, Clone, Parser)]
+/// Generate shell completions
+pub struct Args {
+    #[clap(short, long, help = "Print completions for the given shell", default_value_t = Shell::Powershell)]
+    shell: Shell,
+}
+
+impl super::Command for Args {
+    async fn runner(self, _: &impl ScoopContext) -> Result<(), anyhow::Error> {
+        let completions = crate::completions::generate(self.shell, crate::Args::command());
+
+        print!("{completions}");
+
+        Ok(())
+    }
+}
@@ -12,10 +12,10 @@ use crate::{
 #[derive(Debug, Clone, Parser)]
 /// List the dependencies of a given package, in the order that they will be installed
 pub struct Args {
-    #[clap(help = "The package to list dependencies for")]
+    #[clap(value_name = "PACKAGE", help = "The package to list dependencies for")]
     package: package::Reference,
 
-    #[clap(help = "The bucket of the given package")]
+    #[clap(value_name = "BUCKET", help = "The bucket of the given package")]
     bucket: Option<String>,
 
     // TODO: Implement recursion?
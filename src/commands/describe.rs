@@ -10,7 +10,7 @@ use crate::{
 #[derive(Debug, Clone, Parser)]
 /// Describe a package
 pub struct Args {
-    #[clap(help = "The package to describe")]
+    #[clap(value_name = "PACKAGE", help = "The package to describe")]
     package: String,
 
     #[clap(short, long, help = "The bucket to exclusively search in")]
@@ -1,98 +1,214 @@
-use clap::Parser;
+mod check;
+mod install;
+mod profile;
+
+use std::fmt::Write;
+
+use clap::{Parser, Subcommand};
+use itertools::Itertools;
 use quork::traits::list::ListVariants;
-use sprinkles::{contexts::ScoopContext, shell::Shell};
+use sprinkles::{config, contexts::ScoopContext};
+
+use crate::{config::Config, shell::Shell, validations::Validate};
+
+use super::{CommandHooks as CommandsHooks, CommandRunner, Runnable};
+
+/// A hook, replacing `scoop <name>` with an sfsu command
+pub struct Hook {
+    /// The Scoop command to replace
+    name: String,
+    /// The sfsu command, and any arguments, to run instead
+    command: String,
+}
+
+impl Hook {
+    /// List the enabled hooks, including those defined in the config
+    ///
+    /// # Errors
+    /// - The config could not be loaded, or is invalid
+    pub fn enabled(
+        ctx: &impl ScoopContext,
+        disable: &[CommandsHooks],
+        enabled: &[CommandsHooks],
+    ) -> anyhow::Result<Vec<Self>> {
+        let config = Config::load(ctx)?;
+        config.validate()?;
+
+        let enabled: &[CommandsHooks] = if enabled.is_empty() {
+            &CommandsHooks::VARIANTS
+        } else {
+            enabled
+        };
+
+        // Hooks from the config take precedence over the built in hooks with the same name
+        Ok(enabled
+            .iter()
+            .filter(|variant| !disable.contains(variant))
+            .filter(|variant| !config.hooks.contains_key(variant.hook()))
+            .map(|variant| Hook {
+                name: variant.hook().to_string(),
+                command: variant.command().to_string(),
+            })
+            .chain(config.hooks.into_iter().map(|(name, command)| Hook {
+                name,
+                command: command.split_whitespace().join(" "),
+            }))
+            .collect())
+    }
+}
+
+#[must_use]
+/// Generate the hook script for the given shell
+pub fn script(shell: Shell, hooks: &[Hook]) -> String {
+    let mut script = String::new();
+
+    match shell {
+        Shell::Powershell => {
+            script.push_str("function scoop { switch ($args[0]) { ");
+
+            // I would love to make this all one condition, but Powershell doesn't seem to support that elegantly
+            for Hook { name, command } in hooks {
+                _ = write!(
+                    script,
+                    "  '{name}' {{ return sfsu.exe {command} @($args | Select-Object -Skip 1) }} "
+                );
+            }
+
+            script.push_str("default { scoop.ps1 @args } } }\n");
+        }
+        Shell::Bash | Shell::Zsh => {
+            script.push_str("SCOOP_EXEC=$(which scoop) \nscoop () { \ncase $1 in\n");
+
+            for Hook { name, command } in hooks {
+                _ = writeln!(script, "({name}) sfsu.exe {command} ${{@:2}} ;;");
+            }
+
+            script.push_str("(*) $SCOOP_EXEC $@ ;; \nesac \n} \n");
+        }
+        Shell::Nu => {
+            for Hook { name, command } in hooks {
+                _ = writeln!(
+                    script,
+                    "def --wrapped \"scoop {name}\" [...rest] {{ sfsu {command} ...$rest }}"
+                );
+            }
+        }
+        Shell::Fish => {
+            script.push_str("function scoop\n    switch $argv[1]\n");
+
+            for Hook { name, command } in hooks {
+                _ = writeln!(
+                    script,
+                    "        case '{name}'\n            sfsu.exe {command} $argv[2..-1]"
+                );
+            }
+
+            script.push_str("        case '*'\n            command scoop $argv\n    end\nend\n");
+        }
+        Shell::Elvish => {
+            script.push_str("var sfsu-hooks = [");
+
+            for Hook { name, command } in hooks {
+                _ = write!(script, "&'{name}'=[{command}] ");
+            }
+
+            script.push_str(
+                "]\n\
+                fn scoop {|@args|\n    \
+                    if (and (> (count $args) 0) (has-key $sfsu-hooks $args[0])) {\n        \
+                        sfsu.exe (all $sfsu-hooks[$args[0]]) (all $args[1..])\n    \
+                    } else {\n        \
+                        e:scoop $@args\n    \
+                    }\n\
+                }\n",
+            );
+        }
+    }
+
+    script
+}
 
-use super::CommandHooks as CommandsHooks;
+#[must_use]
+/// Instructions for adding the hooks to the shell's config by hand
+pub fn instructions(shell: Shell) -> Option<String> {
+    let shell_config = shell.config();
+
+    match shell {
+        // TODO: Figure out a way to put these in that PowerShell won't throw a fit about
+        // "# To add this to your config, add the following line to the end of your PowerShell profile:"
+        // "#     Invoke-Expression (&sfsu hook)"
+        Shell::Powershell => None,
+        Shell::Bash | Shell::Zsh => Some(format!(
+            "# Add the following to the end of your ~/.{shell_config} \n\
+            #   source <(sfsu.exe hook --shell {shell})"
+        )),
+        Shell::Nu => Some(format!(
+            "# To add this to your config, run `sfsu hook --shell {shell} | save ~/.cache/sfsu.nu`\n\
+            # And then in your {shell_config} add the following line to the end:\n\
+            #   source ~/.cache/sfsu.nu"
+        )),
+        Shell::Fish => Some(format!(
+            "# Add the following to the end of your {shell_config}\n\
+            #   sfsu.exe hook --shell {shell} | source"
+        )),
+        Shell::Elvish => Some(format!(
+            "# Add the following to the end of your {shell_config}\n\
+            #   eval (sfsu.exe hook --shell {shell} | slurp)"
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Commands {
+    Install(install::Args),
+    Check(check::Args),
+}
+
+impl Runnable for Commands {
+    async fn run(
+        self,
+        ctx: &impl sprinkles::contexts::ScoopContext<Config = sprinkles::config::Scoop>,
+    ) -> anyhow::Result<()> {
+        match self {
+            Commands::Install(args) => args.run(ctx).await,
+            Commands::Check(args) => args.run(ctx).await,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Parser)]
 /// Generate hooks for the given shell
 pub struct Args {
-    #[clap(short = 'D', long, help = "The commands to disable")]
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
+    #[clap(short = 'D', long, global = true, help = "The commands to disable")]
     disable: Vec<CommandsHooks>,
 
-    #[clap(short = 'E', long, help = "The commands to exclusively enable")]
+    #[clap(
+        short = 'E',
+        long,
+        global = true,
+        help = "The commands to exclusively enable"
+    )]
     enabled: Vec<CommandsHooks>,
 
-    #[clap(short, long, help = "Print hooks for the given shell", default_value_t = Shell::Powershell)]
+    #[clap(short, long, global = true, help = "Print hooks for the given shell", default_value_t = Shell::Powershell)]
     shell: Shell,
 }
 
 impl super::Command for Args {
-    async fn runner(self, _: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let shell = self.shell;
-        let shell_config = shell.config();
-        let enabled_hooks: Vec<CommandsHooks> = {
-            // Explicit binding here fixes type inference, as we explicitly cast it to a slice
-            let enabled_hooks: &[CommandsHooks] = if self.enabled.is_empty() {
-                &CommandsHooks::VARIANTS
-            } else {
-                &self.enabled
-            };
-
-            enabled_hooks
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        if let Some(command) = self.command {
+            return command.run(ctx).await;
         }
-        .iter()
-        .filter(|variant| !self.disable.contains(variant))
-        .copied()
-        .collect();
-
-        match shell {
-            Shell::Powershell => {
-                print!("function scoop {{ switch ($args[0]) {{ ");
-
-                // I would love to make this all one condition, but Powershell doesn't seem to support that elegantly
-                for command in enabled_hooks {
-                    print!(
-                        "  '{hook}' {{ return sfsu.exe {command} @($args | Select-Object -Skip 1) }} ",
-                        hook = command.hook(),
-                        command = command.command()
-                    );
-                }
-
-                println!("default {{ scoop.ps1 @args }} }} }}");
-
-                // TODO: Figure out a way to put these in that PowerShell won't throw a fit about
-                // println!("# To add this to your config, add the following line to the end of your PowerShell profile:");
-                // println!("#     Invoke-Expression (&sfsu hook)");
-            }
-            Shell::Bash | Shell::Zsh => {
-                println!(
-                    "SCOOP_EXEC=$(which scoop) \n\
-                    scoop () {{ \n\
-                    case $1 in"
-                );
 
-                for command in enabled_hooks {
-                    println!(
-                        "({hook}) sfsu.exe {command} ${{@:2}} ;;",
-                        hook = command.hook(),
-                        command = command.command()
-                    );
-                }
-
-                println!(
-                    "(*) $SCOOP_EXEC $@ ;; \n\
-                    esac \n\
-                    }} \n\n\
-                    # Add the following to the end of your ~/.{shell_config} \n\
-                    #   source <(sfsu.exe hook --shell {shell})"
-                );
-            }
-            Shell::Nu => {
-                for command in enabled_hooks {
-                    println!(
-                        "def --wrapped \"scoop {hook}\" [...rest] {{ sfsu {command} ...$rest }}",
-                        hook = command.hook(),
-                        command = command.command()
-                    );
-                }
-
-                println!(
-                        "\n# To add this to your config, run `sfsu hook --shell {shell} | save ~/.cache/sfsu.nu`\n\
-                        # And then in your {shell_config} add the following line to the end:\n\
-                        #   source ~/.cache/sfsu.nu"
-                    );
-            }
+        let hooks = Hook::enabled(ctx, &self.disable, &self.enabled)?;
+
+        print!("{}", script(self.shell, &hooks));
+
+        if let Some(instructions) = instructions(self.shell) {
+            println!("\n{instructions}");
         }
 
         Ok(())
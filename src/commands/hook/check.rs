@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use itertools::Itertools;
+use quork::traits::list::ListVariants;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    abandon,
+    commands::CommandHooks,
+    output::colours::{eprintln_green, eprintln_yellow},
+    shell::Shell,
+};
+
+use super::{profile, Hook};
+
+#[derive(Debug, Clone, Parser)]
+/// Check that the hooks installed in your shell's config file match the hooks sfsu would generate now
+pub struct Args {
+    #[clap(
+        long,
+        help = "The config file the hooks are installed in. Defaults to the shell's config file"
+    )]
+    profile: Option<PathBuf>,
+
+    #[clap(from_global)]
+    disable: Vec<CommandHooks>,
+
+    #[clap(from_global)]
+    enabled: Vec<CommandHooks>,
+
+    #[clap(from_global)]
+    shell: Shell,
+}
+
+impl crate::commands::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let path = match self.profile {
+            Some(path) => path,
+            None => self.shell.config_path()?,
+        };
+
+        let contents = if path.exists() {
+            std::fs::read_to_string(&path)?
+        } else {
+            String::new()
+        };
+
+        let Some(range) = profile::find(&contents)
+            .with_context(|| format!("Failed to check the hooks in {}", path.display()))?
+        else {
+            abandon!(
+                "No hooks installed in {}. Run `{}` to install them",
+                path.display(),
+                install_command(self.shell, &self.disable, &self.enabled)
+            );
+        };
+        let installed = &contents[range];
+        let installed_names = profile::names(installed);
+
+        // Without `-D` or `-E`, compare against the hooks that were installed,
+        // so that hooks disabled at install time aren't reported as missing
+        let disable = if self.disable.is_empty() && self.enabled.is_empty() {
+            CommandHooks::VARIANTS
+                .into_iter()
+                .filter(|variant| !installed_names.contains(&variant.hook()))
+                .collect()
+        } else {
+            self.disable
+        };
+        let install = install_command(self.shell, &disable, &self.enabled);
+
+        let hooks = Hook::enabled(ctx, &disable, &self.enabled)?;
+        let expected = profile::block(
+            hooks.iter().map(|hook| hook.name.as_str()),
+            &super::script(self.shell, &hooks),
+        );
+
+        // Normalise line endings, in case the config file was saved with CRLF
+        if installed.replace("\r\n", "\n") == expected {
+            eprintln_green!("Hooks in {} are up to date", path.display());
+            return Ok(());
+        }
+
+        let missing = hooks
+            .iter()
+            .map(|hook| hook.name.as_str())
+            .filter(|name| !installed_names.contains(name))
+            .join(", ");
+        let stale = installed_names
+            .iter()
+            .filter(|name| !hooks.iter().any(|hook| hook.name == **name))
+            .join(", ");
+
+        if !missing.is_empty() {
+            eprintln_yellow!("Missing hooks: {missing}");
+        }
+
+        if !stale.is_empty() {
+            eprintln_yellow!("Installed hooks that would no longer be generated: {stale}");
+        }
+
+        if missing.is_empty() && stale.is_empty() {
+            eprintln_yellow!("The commands run by the installed hooks have changed");
+        }
+
+        abandon!(
+            "Hooks in {} are out of date. Run `{install}` to update them",
+            path.display()
+        );
+    }
+}
+
+/// The command to install the hooks, with the flags that select them
+fn install_command(shell: Shell, disable: &[CommandHooks], enabled: &[CommandHooks]) -> String {
+    let flags = disable
+        .iter()
+        .map(|hook| format!(" -D {}", hook.hook()))
+        .chain(enabled.iter().map(|hook| format!(" -E {}", hook.hook())))
+        .join("");
+
+    format!("sfsu hook install --shell {shell}{flags}")
+}
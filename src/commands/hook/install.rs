@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    commands::CommandHooks,
+    output::colours::{eprintln_green, eprintln_yellow},
+    shell::Shell,
+};
+
+use super::{profile, Hook};
+
+#[derive(Debug, Clone, Parser)]
+/// Install the hooks into your shell's config file, replacing any hooks installed previously
+pub struct Args {
+    #[clap(
+        long,
+        help = "The config file to install the hooks into. Defaults to the shell's config file"
+    )]
+    profile: Option<PathBuf>,
+
+    #[clap(from_global)]
+    disable: Vec<CommandHooks>,
+
+    #[clap(from_global)]
+    enabled: Vec<CommandHooks>,
+
+    #[clap(from_global)]
+    shell: Shell,
+}
+
+impl crate::commands::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let path = match self.profile {
+            Some(path) => path,
+            None => self.shell.config_path()?,
+        };
+
+        let hooks = Hook::enabled(ctx, &self.disable, &self.enabled)?;
+        let block = profile::block(
+            hooks.iter().map(|hook| hook.name.as_str()),
+            &super::script(self.shell, &hooks),
+        );
+
+        let contents = if path.exists() {
+            std::fs::read_to_string(&path)?
+        } else {
+            String::new()
+        };
+
+        let updated = profile::upsert(&contents, &block)
+            .with_context(|| format!("Failed to install hooks into {}", path.display()))?;
+
+        if updated == contents {
+            eprintln_yellow!("Hooks in {} are already up to date", path.display());
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&path, updated)?;
+
+        eprintln_green!(
+            "Installed hooks into {}. Restart your shell to use them",
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! The block of hooks installed into a shell's config file
+//!
+//! The block is wrapped in marker comments, so it can be found and replaced in place on later installs,
+//! rather than appending another copy of the hooks.
+
+use std::ops::Range;
+
+const START: &str = "# >>> sfsu hook >>>";
+const END: &str = "# <<< sfsu hook <<<";
+const HOOKS_PREFIX: &str = "# hooks: ";
+
+#[must_use]
+/// Wrap the hook script in the marker comments
+///
+/// The names of the hooks are included, so that `sfsu hook check` can report which hooks have changed
+pub fn block<'a>(names: impl IntoIterator<Item = &'a str>, script: &str) -> String {
+    let names = names.into_iter().collect::<Vec<_>>().join(" ");
+
+    format!(
+        "{START}\n\
+        # Generated by `sfsu hook install`. Any changes will be overwritten\n\
+        {HOOKS_PREFIX}{names}\n\
+        {script}{END}\n"
+    )
+}
+
+/// Find the installed block in the config file's contents, including the marker comments
+///
+/// # Errors
+/// - The start marker is present without the end marker, i.e the block was edited by hand
+pub fn find(contents: &str) -> anyhow::Result<Option<Range<usize>>> {
+    let Some(start) = contents.find(START) else {
+        return Ok(None);
+    };
+
+    // Replacing to the end of the file, or appending another block, could lose the user's config
+    let Some(end) = contents[start..].find(END) else {
+        anyhow::bail!(
+            "Found the start of the sfsu hook block (\"{START}\"), but not its end (\"{END}\"). Remove the hook block, or add the end marker after it, and try again"
+        );
+    };
+    let end = start + end + END.len();
+
+    // Include the trailing newline, so that replacing the block doesn't leave an empty line behind
+    let end = if contents[end..].starts_with("\r\n") {
+        end + 2
+    } else if contents[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+
+    Ok(Some(start..end))
+}
+
+#[must_use]
+/// List the names of the hooks in an installed block
+pub fn names(block: &str) -> Vec<&str> {
+    block
+        .lines()
+        .find_map(|line| line.strip_prefix(HOOKS_PREFIX))
+        .map(|names| names.split_whitespace().collect())
+        .unwrap_or_default()
+}
+
+/// Insert the block into the config file's contents, replacing the installed block if there is one
+///
+/// # Errors
+/// - The installed block is missing its end marker
+pub fn upsert(contents: &str, block: &str) -> anyhow::Result<String> {
+    if let Some(range) = find(contents)? {
+        let mut contents = contents.to_string();
+        contents.replace_range(range, block);
+        return Ok(contents);
+    }
+
+    Ok(if contents.is_empty() || contents.ends_with('\n') {
+        format!("{contents}{block}")
+    } else {
+        format!("{contents}\n{block}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_is_idempotent() {
+        let first = block(["search", "list"], "hooks v1\n");
+        let second = block(["search"], "hooks v2\n");
+
+        let contents = "# existing config\nalias ls=eza";
+        let installed = upsert(contents, &first).unwrap();
+
+        assert_eq!(upsert(&installed, &first).unwrap(), installed);
+
+        let updated = upsert(&installed, &second).unwrap();
+
+        assert!(updated.starts_with("# existing config\nalias ls=eza\n"));
+        assert!(updated.contains("hooks v2"));
+        assert!(!updated.contains("hooks v1"));
+        assert_eq!(updated.matches(START).count(), 1);
+    }
+
+    #[test]
+    fn test_names() {
+        let contents = upsert("", &block(["search", "list"], "hooks\n")).unwrap();
+        let range = find(&contents).unwrap().unwrap();
+
+        assert_eq!(names(&contents[range]), vec!["search", "list"]);
+    }
+
+    #[test]
+    fn test_missing_end_marker() {
+        let installed = upsert("alias ls=eza\n", &block(["search"], "hooks v1\n")).unwrap();
+        let edited = installed.replace(END, "");
+
+        assert!(find(&edited).is_err());
+        assert!(upsert(&edited, &block(["search"], "hooks v2\n")).is_err());
+    }
+}
@@ -31,6 +31,12 @@ pub struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
+    #[clap(
+        long,
+        help = "Check every bucket for a newer version of each app, not just the bucket it was installed from"
+    )]
+    all_buckets: bool,
+
     #[clap(from_global)]
     json: bool,
 }
@@ -48,9 +54,12 @@ impl Command for Args {
             if self.json {
                 let mut map = Map::new();
 
-                let apps = apps::Args { json: self.json }
-                    .run_direct(ctx, false)?
-                    .unwrap_or_default();
+                let apps = apps::Args {
+                    all_buckets: self.all_buckets,
+                    json: self.json,
+                }
+                .run_direct(ctx, false)?
+                .unwrap_or_default();
 
                 let buckets = buckets::Args { json: self.json }
                     .run_direct(ctx, false)?
@@ -64,9 +73,12 @@ impl Command for Args {
                 println!("{output}");
             } else {
                 println!("Outdated Apps:");
-                Commands::Apps(apps::Args { json: self.json })
-                    .run(ctx)
-                    .await?;
+                Commands::Apps(apps::Args {
+                    all_buckets: self.all_buckets,
+                    json: self.json,
+                })
+                .run(ctx)
+                .await?;
                 println!("\nOutdated Buckets:");
                 Commands::Buckets(buckets::Args { json: self.json })
                     .run(ctx)
@@ -3,11 +3,20 @@ use rayon::prelude::*;
 use serde_json::Value;
 use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::models::install};
 
-use crate::{models::outdated::Info, output::structured::Structured};
+use crate::{
+    models::{outdated::Info, status::Alternative},
+    output::structured::Structured,
+};
 
 #[derive(Debug, Clone, Parser)]
 /// List outdated apps
 pub struct Args {
+    #[clap(
+        long,
+        help = "Check every bucket for a newer version, not just the bucket each app was installed from"
+    )]
+    pub(super) all_buckets: bool,
+
     #[clap(from_global)]
     pub(super) json: bool,
 }
@@ -32,17 +41,33 @@ impl Args {
     ) -> Result<Option<Vec<Value>>, anyhow::Error> {
         let apps = install::Manifest::list_all_unchecked(ctx)?;
 
+        let all_buckets = if self.all_buckets {
+            Bucket::list_all(ctx)?
+        } else {
+            vec![]
+        };
+
         let mut outdated: Vec<Info> = apps
             .par_iter()
             .flat_map(|app| -> anyhow::Result<Info> {
                 if let Some(bucket) = &app.bucket {
                     let local_manifest = app.get_manifest(ctx)?;
-                    // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
+                    let bucket_name = bucket;
                     let bucket = Bucket::from_name(ctx, bucket)?;
 
                     let remote_manifest = bucket.get_manifest(unsafe { app.name() })?;
 
-                    if let Some(info) = Info::from_manifests(&local_manifest, &remote_manifest) {
+                    let alternative = Alternative::find(
+                        &all_buckets,
+                        unsafe { app.name() },
+                        bucket_name,
+                        &local_manifest.version.to_string(),
+                        &remote_manifest.version.to_string(),
+                    );
+
+                    if let Some(info) =
+                        Info::from_manifests(&local_manifest, &remote_manifest, alternative)
+                    {
                         Ok(info)
                     } else {
                         anyhow::bail!("no update available")
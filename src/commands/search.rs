@@ -1,16 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 use rayon::prelude::*;
 
-use clap::Parser;
-use regex::Regex;
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
 
 use sprinkles::{
     buckets::Bucket,
     contexts::ScoopContext,
-    packages::{Manifest, MergeDefaults, SearchMode},
-    version::Version,
+    packages::{InstallManifest, SearchMode},
     Architecture,
 };
 
@@ -20,14 +19,121 @@ use crate::{
         sectioned::{Children, Section, Sections, Text},
         warning,
     },
+    pattern::{Pattern, Syntax, SyntaxArgs},
 };
 
+mod fuzzy;
+mod grouped;
+mod index;
+mod interactive;
+mod known;
+
+use grouped::GroupedApp;
+use index::{BucketIndex, Entry};
+use interactive::Picker;
+use known::KnownBucket;
+
+#[derive(Debug, Clone)]
+/// The method used to match manifests against the search pattern
+pub enum Matcher {
+    /// Match using a glob, regex or literal pattern
+    Pattern(Pattern),
+    /// Match using typo-tolerant fuzzy scoring
+    Fuzzy(fuzzy::Pattern),
+}
+
+impl Matcher {
+    /// Score how well the haystack matches, returning [`None`] if it does not match
+    fn score(&self, haystack: &str) -> Option<u32> {
+        match self {
+            Matcher::Pattern(pattern) => {
+                let found = pattern.find(haystack)?;
+
+                if found.len() == haystack.len() {
+                    Some(fuzzy::EXACT)
+                } else {
+                    // Rank matches covering more of the haystack, or found earlier in it, higher
+                    let coverage = found.len() * 500 / haystack.len().max(1);
+                    let at_start = if found.start() == 0 { 100 } else { 0 };
+
+                    u32::try_from(coverage + at_start).ok()
+                }
+            }
+            Matcher::Fuzzy(pattern) => pattern.score(haystack),
+        }
+    }
+
+    /// Score how well free text matches, returning [`None`] if it does not match
+    fn score_text(&self, text: &str) -> Option<u32> {
+        match self {
+            Matcher::Pattern(_) => self.score(text),
+            Matcher::Fuzzy(pattern) => pattern.score_words(text),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// A manifest field that can be searched
+pub enum Field {
+    /// The package name
+    Name,
+    /// The binaries the package provides
+    Binaries,
+    /// The package description
+    Description,
+    /// The package homepage
+    Homepage,
+    /// The package license
+    License,
+}
+
+impl Field {
+    /// Convert a search mode into the fields it searches
+    fn from_mode(mode: SearchMode) -> Vec<Self> {
+        let mut fields = vec![];
+
+        if mode.match_names() {
+            fields.push(Field::Name);
+        }
+
+        if mode.match_binaries() {
+            fields.push(Field::Binaries);
+        }
+
+        fields
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Name => write!(f, "Name"),
+            Field::Binaries => write!(f, "Binaries"),
+            Field::Description => write!(f, "Description"),
+            Field::Homepage => write!(f, "Homepage"),
+            Field::License => write!(f, "License"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A field that matched the search pattern, and the text that matched
+struct FieldMatch {
+    field: Field,
+    text: String,
+}
+
 #[derive(Debug, Clone)]
 #[must_use = "MatchCriteria has no side effects"]
 /// The criteria for a match
 pub struct MatchCriteria {
     name: bool,
     bins: Vec<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+    license: Option<String>,
+    score: u32,
 }
 
 impl MatchCriteria {
@@ -36,51 +142,110 @@ impl MatchCriteria {
         Self {
             name: false,
             bins: vec![],
+            description: None,
+            homepage: None,
+            license: None,
+            score: 0,
         }
     }
 
-    /// Check if the name matches
-    pub fn matches(
-        file_name: &str,
-        pattern: &Regex,
-        list_binaries: impl FnOnce() -> Vec<String>,
-        mode: SearchMode,
-    ) -> Self {
+    /// Check which of the given fields match
+    pub fn matches(entry: &Entry, pattern: &Matcher, fields: &[Field]) -> Self {
         let mut output = MatchCriteria::new();
 
-        if mode.match_names() {
-            output.match_names(pattern, file_name);
-        }
-
-        if mode.match_binaries() {
-            output.match_binaries(pattern, list_binaries());
+        for field in fields {
+            match field {
+                Field::Name => {
+                    output.match_names(pattern, &entry.name);
+                }
+                Field::Binaries => {
+                    output.match_binaries(pattern, &entry.bins);
+                }
+                Field::Description => {
+                    output.description = output.match_text(pattern, entry.description.as_ref());
+                }
+                Field::Homepage => {
+                    output.homepage = output.match_text(pattern, entry.homepage.as_ref());
+                }
+                Field::License => {
+                    output.license = output.match_text(pattern, entry.license.as_ref());
+                }
+            }
         }
 
         output
     }
 
-    fn match_names(&mut self, pattern: &Regex, file_name: &str) -> &mut Self {
-        if pattern.is_match(file_name) {
+    fn match_names(&mut self, pattern: &Matcher, file_name: &str) -> &mut Self {
+        if let Some(score) = pattern.score(file_name) {
             self.name = true;
+            self.score = self.score.max(score);
         }
         self
     }
 
-    fn match_binaries(&mut self, pattern: &Regex, binaries: Vec<String>) -> &mut Self {
-        let binary_matches = binaries
-            .into_iter()
-            .filter(|binary| pattern.is_match(binary))
-            .filter_map(|b| {
-                if pattern.is_match(&b) {
-                    Some(b.clone())
-                } else {
-                    None
-                }
+    fn match_binaries(&mut self, pattern: &Matcher, binaries: &[String]) -> &mut Self {
+        for binary in binaries {
+            if let Some(score) = pattern.score(binary) {
+                self.score = self.score.max(score);
+                self.bins.push(binary.clone());
+            }
+        }
+
+        self
+    }
+
+    fn match_text(&mut self, pattern: &Matcher, text: Option<&String>) -> Option<String> {
+        let text = text?;
+        let score = pattern.score_text(text)?;
+
+        // Matches in free text are less relevant than matches in names or binaries
+        self.score = self.score.max(score / 2);
+
+        Some(text.clone())
+    }
+
+    /// Check if any field matched
+    pub fn any(&self) -> bool {
+        self.name
+            || !self.bins.is_empty()
+            || self.description.is_some()
+            || self.homepage.is_some()
+            || self.license.is_some()
+    }
+
+    /// List the free text fields that matched, alongside the matching text
+    fn text_matches(&self) -> impl Iterator<Item = (Field, &String)> {
+        [
+            (Field::Description, self.description.as_ref()),
+            (Field::Homepage, self.homepage.as_ref()),
+            (Field::License, self.license.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, text)| text.map(|text| (field, text)))
+    }
+
+    fn into_field_matches(self, name: &str) -> Vec<FieldMatch> {
+        let mut matches = vec![];
+
+        if self.name {
+            matches.push(FieldMatch {
+                field: Field::Name,
+                text: name.to_string(),
             });
+        }
 
-        self.bins.extend(binary_matches);
+        matches.extend(self.text_matches().map(|(field, text)| FieldMatch {
+            field,
+            text: text.clone(),
+        }));
 
-        self
+        matches.extend(self.bins.into_iter().map(|bin| FieldMatch {
+            field: Field::Binaries,
+            text: bin,
+        }));
+
+        matches
     }
 }
 
@@ -90,107 +255,122 @@ impl Default for MatchCriteria {
     }
 }
 
+/// Installed app names, mapped to the bucket they were installed from
+struct InstalledApps(HashMap<String, String>);
+
+impl InstalledApps {
+    fn list(ctx: &impl ScoopContext) -> anyhow::Result<Self> {
+        let apps = InstallManifest::list_all_unchecked(ctx)?
+            .into_iter()
+            .filter_map(|app| {
+                let bucket = app.bucket.clone()?;
+
+                Some((unsafe { app.name() }.to_string(), bucket))
+            })
+            .collect();
+
+        Ok(Self(apps))
+    }
+
+    fn contains(&self, entry: &Entry) -> bool {
+        self.0.get(&entry.name) == Some(&entry.bucket)
+    }
+}
+
 struct MatchedManifest {
-    manifest: Manifest,
+    entry: Entry,
     installed: bool,
-    name_matched: bool,
-    bins: Vec<String>,
+    criteria: MatchCriteria,
     exact_match: bool,
 }
 
 impl MatchedManifest {
     pub fn new(
-        ctx: &impl ScoopContext,
-        manifest: Manifest,
-        pattern: &Regex,
-        mode: SearchMode,
-        arch: Architecture,
+        entry: Entry,
+        installed_apps: &InstalledApps,
+        pattern: &Matcher,
+        raw_pattern: &str,
+        case_sensitive: bool,
+        fields: &[Field],
     ) -> MatchedManifest {
         // TODO: Better display of output
-        let bucket = unsafe { manifest.bucket() };
-
-        let match_output = MatchCriteria::matches(
-            unsafe { manifest.name() },
-            pattern,
-            // Function to list binaries from a manifest
-            // Passed as a closure to avoid this parsing if bin matching isn't required
-            || {
-                manifest
-                    .architecture
-                    .merge_default(manifest.install_config.clone(), arch)
-                    .bin
-                    .map(|b| b.to_vec())
-                    .unwrap_or_default()
-            },
-            mode,
-        );
+        let criteria = MatchCriteria::matches(&entry, pattern, fields);
 
-        let installed = manifest.is_installed(ctx, Some(bucket));
-        let exact_match = unsafe { manifest.name() } == pattern.to_string();
+        let installed = installed_apps.contains(&entry);
+        let exact_match = if case_sensitive {
+            entry.name == raw_pattern
+        } else {
+            entry.name.eq_ignore_ascii_case(raw_pattern)
+        };
 
         MatchedManifest {
-            manifest,
+            entry,
             installed,
-            name_matched: match_output.name,
-            bins: match_output.bins,
+            criteria,
             exact_match,
         }
     }
 
+    /// Order by relevance, with exact matches first, then by score, then by name
+    fn cmp_relevance(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .exact_match
+            .cmp(&self.exact_match)
+            .then_with(|| other.criteria.score.cmp(&self.criteria.score))
+            .then_with(|| self.entry.name.cmp(&other.entry.name))
+    }
+
     pub fn should_match(&self, installed_only: bool) -> bool {
         if !self.installed && installed_only {
             return false;
         }
-        if !self.name_matched && self.bins.is_empty() {
-            return false;
-        }
 
-        true
+        self.criteria.any()
     }
 
     pub fn to_section(&self) -> Section<Text<String>> {
         let styled_package_name = if self.exact_match {
-            console::style(unsafe { self.manifest.name() })
-                .bold()
-                .to_string()
+            console::style(&self.entry.name).bold().to_string()
         } else {
-            unsafe { self.manifest.name() }.to_string()
+            self.entry.name.clone()
         };
 
         let installed_text = if self.installed { "[installed] " } else { "" };
 
         let title = format!(
             "{styled_package_name} ({}) {installed_text}",
-            self.manifest.version
+            self.entry.version
         );
 
-        if self.bins.is_empty() {
-            Section::new(Children::None)
-        } else {
-            let bins = self
-                .bins
-                .iter()
-                .map(|output| {
-                    Text::new(format!(
-                        "{}{}",
-                        crate::output::WHITESPACE,
-                        console::style(output).bold()
-                    ))
-                })
-                .collect_vec();
+        let texts = self.criteria.text_matches().map(|(field, text)| {
+            Text::new(format!(
+                "{}{field}: {}",
+                crate::output::WHITESPACE,
+                console::style(text).bold()
+            ))
+        });
 
-            Section::new(Children::from(bins))
-        }
-        .with_title(title)
+        let bins = self.criteria.bins.iter().map(|output| {
+            Text::new(format!(
+                "{}{}",
+                crate::output::WHITESPACE,
+                console::style(output).bold()
+            ))
+        });
+
+        Section::new(Children::from(texts.chain(bins).collect_vec())).with_title(title)
     }
 
-    pub fn into_output(self) -> MatchedOutput {
+    pub fn into_output(self, add_command: Option<String>) -> MatchedOutput {
         MatchedOutput {
-            name: unsafe { self.manifest.name() }.to_string(),
-            bucket: unsafe { self.manifest.bucket() }.to_string(),
-            version: self.manifest.version.clone(),
+            add_command,
             installed: self.installed,
-            bins: self.bins,
+            bins: self.criteria.bins.clone(),
+            score: self.criteria.score,
+            matches: self.criteria.into_field_matches(&self.entry.name),
+            name: self.entry.name,
+            bucket: self.entry.bucket,
+            version: self.entry.version,
         }
     }
 }
@@ -201,20 +381,27 @@ impl std::fmt::Display for MatchedManifest {
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Serialize)]
 struct MatchedOutput {
     name: String,
     bucket: String,
-    version: Version,
+    version: String,
     installed: bool,
     bins: Vec<String>,
+    score: u32,
+    matches: Vec<FieldMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    add_command: Option<String>,
 }
 
 #[derive(Debug, Clone, Parser)]
 /// Search for a package
 pub struct Args {
-    #[clap(help = "The regex pattern to search for, using Rust Regex syntax")]
-    pattern: String,
+    #[clap(
+        help = "The pattern to search for. Uses Rust Regex syntax unless another syntax is chosen",
+        required_unless_present_any = ["rebuild_index", "interactive"]
+    )]
+    pattern: Option<String>,
 
     #[clap(
         short,
@@ -232,6 +419,56 @@ pub struct Args {
     #[clap(short, long, help = "Search mode to use", default_value_t)]
     mode: SearchMode,
 
+    #[clap(
+        long = "field",
+        help = "The manifest fields to search. Overrides the search mode if provided",
+        value_delimiter = ','
+    )]
+    fields: Vec<Field>,
+
+    #[clap(
+        short,
+        long,
+        help = "Use typo-tolerant fuzzy matching instead of regex, ranking results by relevance",
+        conflicts_with_all = ["glob", "regex", "literal"]
+    )]
+    fuzzy: bool,
+
+    #[clap(flatten)]
+    syntax: SyntaxArgs,
+
+    #[clap(
+        long,
+        help = "Parse every manifest instead of using the search index",
+        conflicts_with = "rebuild_index"
+    )]
+    no_index: bool,
+
+    #[clap(
+        long,
+        help = "Rebuild the search index for every searched bucket. The pattern may be omitted to only rebuild the index"
+    )]
+    rebuild_index: bool,
+
+    #[clap(
+        long,
+        help = "Also search known buckets that haven't been added. They are cloned into the Scoop cache the first time"
+    )]
+    known: bool,
+
+    #[clap(
+        long,
+        help = "Show one result per app, listing every bucket it is in and the version each provides"
+    )]
+    group_by_app: bool,
+
+    #[clap(
+        long,
+        help = "Pick a package interactively, filtering as you type. The chosen package is printed as `bucket/app`",
+        conflicts_with_all = ["group_by_app", "rebuild_index"]
+    )]
+    interactive: bool,
+
     #[clap(from_global)]
     arch: Architecture,
 
@@ -241,16 +478,23 @@ pub struct Args {
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let (bucket, raw_pattern) = if let Some((bucket, raw_pattern)) =
-            self.pattern.split_once('/')
-        {
+        if self.interactive {
+            return self.pick(ctx).await;
+        }
+
+        let Some(ref pattern) = self.pattern else {
+            // Only possible when rebuilding the index, as the pattern is otherwise required
+            return self.rebuild_all(ctx).await;
+        };
+
+        let (bucket, raw_pattern) = if let Some((bucket, raw_pattern)) = pattern.split_once('/') {
             warning!("bucket/package syntax is deprecated. Please use the --bucket flag instead");
             (
                 Some({
                     // Bucket flag overrides bucket/package syntax
-                    if let Some(bucket) = self.bucket {
+                    if let Some(ref bucket) = self.bucket {
                         warning!("Using bucket flag instead of bucket/package syntax");
-                        bucket
+                        bucket.clone()
                     } else {
                         bucket.to_string()
                     }
@@ -258,59 +502,103 @@ impl super::Command for Args {
                 raw_pattern.to_string(),
             )
         } else {
-            (self.bucket, self.pattern)
+            (self.bucket.clone(), pattern.clone())
         };
 
-        let pattern = {
-            Regex::new(&format!(
-                "{}{raw_pattern}",
-                if self.case_sensitive { "" } else { "(?i)" },
-            ))
-            .calm_expect(
-                "Invalid Regex provided. See https://docs.rs/regex/latest/regex/ for more info",
-            )
-        };
+        let pattern = self.matcher(&raw_pattern).calm_expect(
+            "Invalid pattern provided. See https://docs.rs/regex/latest/regex/ for regex syntax",
+        );
+
+        let fields = self.fields();
+
+        let installed_apps = InstalledApps::list(ctx)?;
 
-        let matching_buckets: Vec<Bucket> =
-            if let Some(Ok(bucket)) = bucket.map(|name| Bucket::from_name(ctx, name)) {
-                vec![bucket]
+        let (matching_buckets, known_buckets) = self.list_buckets(ctx, bucket.as_deref()).await?;
+
+        let match_entries = |bucket: String, entries: Vec<Entry>| {
+            let mut matches = entries
+                .into_par_iter()
+                .map(|entry| {
+                    MatchedManifest::new(
+                        entry,
+                        &installed_apps,
+                        &pattern,
+                        &raw_pattern,
+                        self.case_sensitive,
+                        &fields,
+                    )
+                })
+                .filter(|matched_manifest| matched_manifest.should_match(self.installed))
+                .collect::<Vec<_>>();
+
+            if matches.is_empty() {
+                None
             } else {
-                Bucket::list_all(ctx)?
-            };
+                matches.par_sort_by(MatchedManifest::cmp_relevance);
 
-        let buckets: HashMap<String, Vec<MatchedManifest>> = matching_buckets
+                Some((bucket, matches))
+            }
+        };
+
+        let mut buckets: HashMap<String, Vec<MatchedManifest>> = matching_buckets
             .par_iter()
-            .filter_map(
-                |bucket| match bucket.matches(ctx, self.installed, &pattern, self.mode) {
-                    Ok(manifests) => {
-                        let matches = manifests
-                            .into_par_iter()
-                            .map(|manifest| {
-                                MatchedManifest::new(ctx, manifest, &pattern, self.mode, self.arch)
-                            })
-                            .filter(|matched_manifest| {
-                                matched_manifest.should_match(self.installed)
-                            })
-                            .collect::<Vec<_>>();
-
-                        if matches.is_empty() {
-                            None
-                        } else {
-                            Some((bucket.name().to_string(), matches))
-                        }
+            .filter_map(|bucket| {
+                let entries = match self.list_entries(ctx, bucket, Some(&pattern)) {
+                    Ok(entries) => entries,
+                    Err(err) => {
+                        error!("Failed to search bucket {}: {err:?}", bucket.name());
+                        return None;
                     }
-                    _ => None,
-                },
-            )
+                };
+
+                match_entries(bucket.name().to_string(), entries)
+            })
             .collect();
 
+        let not_added: HashSet<String> = known_buckets
+            .iter()
+            .map(|bucket| bucket.name.clone())
+            .collect();
+
+        buckets.par_extend(known_buckets.par_iter().filter_map(|bucket| {
+            let entries = match bucket.entries(ctx, self.arch, self.rebuild_index) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    error!("Failed to search known bucket {}: {err:?}", bucket.name);
+                    return None;
+                }
+            };
+
+            match_entries(bucket.name.clone(), entries)
+        }));
+
+        let add_command = |bucket: &str| {
+            not_added
+                .contains(bucket)
+                .then(|| KnownBucket::add_command(bucket))
+        };
+
+        if self.group_by_app {
+            let apps = GroupedApp::group(buckets, add_command);
+
+            if self.json {
+                serde_json::to_writer_pretty(std::io::stdout(), &apps)?;
+            } else {
+                let apps: Sections<_> = apps.iter().map(GroupedApp::to_section).collect();
+
+                print!("{apps}");
+            }
+
+            return Ok(());
+        }
+
         if self.json {
             let json_matches: HashMap<String, Vec<MatchedOutput>> = buckets
                 .into_iter()
                 .map(|(bucket, matches)| {
                     let bucket_matches: Vec<MatchedOutput> = matches
                         .into_iter()
-                        .map(MatchedManifest::into_output)
+                        .map(|matched| matched.into_output(add_command(&bucket)))
                         .collect();
 
                     (bucket, bucket_matches)
@@ -329,7 +617,15 @@ impl super::Command for Args {
                         .map(MatchedManifest::to_section)
                         .collect_into_vec(&mut sections);
 
-                    Section::new(Children::from(sections)).with_title(format!("'{bucket}' bucket:"))
+                    let title = match add_command(&bucket) {
+                        Some(add_command) => format!(
+                            "'{bucket}' bucket ({}, run `{add_command}`):",
+                            console::style("bucket not added").yellow()
+                        ),
+                        None => format!("'{bucket}' bucket:"),
+                    };
+
+                    Section::new(Children::from(sections)).with_title(title)
                 })
                 .collect();
 
@@ -341,3 +637,158 @@ impl super::Command for Args {
         Ok(())
     }
 }
+
+impl Args {
+    fn matcher(&self, raw_pattern: &str) -> Result<Matcher, regex::Error> {
+        if self.fuzzy {
+            Ok(Matcher::Fuzzy(fuzzy::Pattern::new(
+                raw_pattern,
+                self.case_sensitive,
+            )))
+        } else {
+            Pattern::new(
+                raw_pattern,
+                self.syntax.syntax_or(Syntax::Regex),
+                self.case_sensitive,
+            )
+            .map(Matcher::Pattern)
+        }
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        if self.fields.is_empty() {
+            Field::from_mode(self.mode)
+        } else {
+            self.fields.clone()
+        }
+    }
+
+    /// List the buckets to search, and the known buckets to search if `--known` was passed
+    async fn list_buckets(
+        &self,
+        ctx: &impl ScoopContext,
+        bucket: Option<&str>,
+    ) -> anyhow::Result<(Vec<Bucket>, Vec<KnownBucket>)> {
+        let buckets = match bucket.map(|name| Bucket::from_name(ctx, name)) {
+            Some(Ok(bucket)) => vec![bucket],
+            // The bucket may be a known bucket that hasn't been added
+            Some(Err(_)) if self.known => vec![],
+            _ => Bucket::list_all(ctx)?,
+        };
+
+        let known_buckets = if self.known {
+            let known_buckets = KnownBucket::list_not_added(ctx, bucket);
+            KnownBucket::clone_all(&known_buckets, false).await?;
+            known_buckets
+        } else {
+            vec![]
+        };
+
+        Ok((buckets, known_buckets))
+    }
+
+    /// List the manifest summaries in a bucket, using the search index unless it is disabled
+    fn list_entries(
+        &self,
+        ctx: &impl ScoopContext,
+        bucket: &Bucket,
+        pattern: Option<&Matcher>,
+    ) -> anyhow::Result<Vec<Entry>> {
+        if !self.no_index {
+            return BucketIndex::entries(ctx, bucket, self.arch, self.rebuild_index);
+        }
+
+        match pattern {
+            Some(Matcher::Pattern(pattern)) if self.fields.is_empty() => Ok(bucket
+                .matches(ctx, self.installed, pattern.as_regex(), self.mode)?
+                .iter()
+                .map(|manifest| Entry::from_manifest(manifest, self.arch))
+                .collect()),
+            // Fuzzy matching and field matching can't be used to pre-filter manifests,
+            // so every manifest is checked
+            _ => BucketIndex::parse(bucket, self.arch),
+        }
+    }
+
+    async fn pick(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let (buckets, known_buckets) = self.list_buckets(ctx, self.bucket.as_deref()).await?;
+
+        let mut entries = buckets
+            .par_iter()
+            .filter_map(|bucket| match self.list_entries(ctx, bucket, None) {
+                Ok(entries) => Some(entries),
+                Err(err) => {
+                    error!("Failed to list bucket {}: {err:?}", bucket.name());
+                    None
+                }
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        entries.par_extend(
+            known_buckets
+                .par_iter()
+                .filter_map(
+                    |bucket| match bucket.entries(ctx, self.arch, self.rebuild_index) {
+                        Ok(entries) => Some(entries),
+                        Err(err) => {
+                            error!("Failed to list known bucket {}: {err:?}", bucket.name);
+                            None
+                        }
+                    },
+                )
+                .flatten(),
+        );
+
+        let installed_apps = InstalledApps::list(ctx)?;
+
+        if self.installed {
+            entries.retain(|entry| installed_apps.contains(entry));
+        }
+
+        entries.par_sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.bucket.cmp(&b.bucket)));
+
+        let query = self.pattern.clone().unwrap_or_default();
+
+        if let Some(entry) = Picker::new(self, entries, installed_apps, query).run()? {
+            println!("{}/{}", entry.bucket, entry.name);
+        }
+
+        Ok(())
+    }
+
+    async fn rebuild_all(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let buckets = match Bucket::one_or_all(ctx, self.bucket.clone()) {
+            Ok(buckets) => buckets,
+            // The bucket may be a known bucket that hasn't been added
+            Err(_) if self.known && self.bucket.is_some() => vec![],
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut entries = buckets
+            .par_iter()
+            .map(|bucket| BucketIndex::entries(ctx, bucket, self.arch, true))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.known {
+            let known_buckets = KnownBucket::list_not_added(ctx, self.bucket.as_deref());
+            // Rebuilding also refreshes the clones, so that new manifests are picked up
+            KnownBucket::clone_all(&known_buckets, true).await?;
+
+            entries.extend(
+                known_buckets
+                    .par_iter()
+                    .map(|bucket| bucket.entries(ctx, self.arch, true))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        eprintln!(
+            "Rebuilt search index for {} buckets ({} manifests)",
+            entries.len(),
+            entries.iter().map(Vec::len).sum::<usize>()
+        );
+
+        Ok(())
+    }
+}
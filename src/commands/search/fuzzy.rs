@@ -0,0 +1,229 @@
+//! Typo-tolerant fuzzy matching
+//!
+//! Scores are relative, and only meaningful when compared against other scores from the same pattern.
+
+/// The score given to an exact match
+pub const EXACT: u32 = 1000;
+
+const PREFIX: u32 = 900;
+const SUBSTRING: u32 = 800;
+const SUBSEQUENCE: u32 = 600;
+const TYPO: u32 = 400;
+
+#[derive(Debug, Clone)]
+/// A fuzzy search pattern
+pub struct Pattern {
+    needle: Vec<char>,
+    case_sensitive: bool,
+}
+
+impl Pattern {
+    #[must_use]
+    /// Create a new fuzzy pattern
+    pub fn new(pattern: &str, case_sensitive: bool) -> Self {
+        Self {
+            needle: Self::normalize(pattern, case_sensitive),
+            case_sensitive,
+        }
+    }
+
+    fn normalize(text: &str, case_sensitive: bool) -> Vec<char> {
+        if case_sensitive {
+            text.chars().collect()
+        } else {
+            text.chars().flat_map(char::to_lowercase).collect()
+        }
+    }
+
+    #[must_use]
+    /// Score how well the haystack matches the pattern
+    ///
+    /// Returns [`None`] if the haystack does not match at all
+    pub fn score(&self, haystack: &str) -> Option<u32> {
+        let haystack = Self::normalize(haystack, self.case_sensitive);
+        let needle = self.needle.as_slice();
+
+        if needle.is_empty() {
+            return Some(0);
+        }
+
+        let extra = penalty(haystack.len().saturating_sub(needle.len()));
+
+        if haystack == needle {
+            return Some(EXACT);
+        }
+
+        if haystack.starts_with(needle) {
+            return Some(PREFIX - extra.min(99));
+        }
+
+        if let Some(start) = haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+        {
+            return Some(SUBSTRING - (penalty(start) * 2 + extra).min(199));
+        }
+
+        if let Some(gaps) = subsequence_gaps(needle, &haystack) {
+            return Some(SUBSEQUENCE - (penalty(gaps) * 4 + extra).min(199));
+        }
+
+        let prefix_len = needle.len().min(haystack.len());
+        let distance = osa_distance(needle, &haystack)
+            .min(osa_distance(needle, &haystack[..prefix_len]) + usize::from(extra > 0));
+
+        if distance <= max_typos(needle.len()) {
+            return Some(TYPO - (penalty(distance) * 100 + extra).min(399));
+        }
+
+        None
+    }
+
+    #[must_use]
+    /// Score how well free text (i.e a description) matches the pattern
+    ///
+    /// The pattern is compared against each run of words in the text with the same number of words as the pattern,
+    /// so that short patterns don't loosely match long text
+    pub fn score_words(&self, text: &str) -> Option<u32> {
+        let pattern_words = self
+            .needle
+            .split(|c| c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .count()
+            .max(1);
+        let words = text.split_whitespace().collect::<Vec<_>>();
+
+        if words.is_empty() {
+            return None;
+        }
+
+        words
+            .windows(pattern_words.min(words.len()))
+            .filter_map(|window| self.score(&window.join(" ")))
+            .max()
+    }
+}
+
+fn penalty(value: usize) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+/// The maximum number of typos allowed for a pattern of the given length
+fn max_typos(len: usize) -> usize {
+    match len {
+        0..=2 => 0,
+        3..=4 => 1,
+        5..=8 => 2,
+        _ => 3,
+    }
+}
+
+/// Returns the number of skipped characters between the first and last matched character,
+/// or [`None`] if the needle is not a subsequence of the haystack
+fn subsequence_gaps(needle: &[char], haystack: &[char]) -> Option<usize> {
+    let mut needle_chars = needle.iter().peekable();
+    let mut first = None;
+    let mut last = 0;
+
+    for (i, c) in haystack.iter().enumerate() {
+        let Some(next) = needle_chars.peek() else {
+            break;
+        };
+
+        if *next == c {
+            first.get_or_insert(i);
+            last = i;
+            needle_chars.next();
+        }
+    }
+
+    if needle_chars.peek().is_some() {
+        return None;
+    }
+
+    first.map(|first| last - first + 1 - needle.len())
+}
+
+/// Optimal string alignment distance (Levenshtein distance with adjacent transpositions)
+fn osa_distance(a: &[char], b: &[char]) -> usize {
+    let width = b.len() + 1;
+    let mut matrix = vec![0; (a.len() + 1) * width];
+
+    for i in 0..=a.len() {
+        matrix[i * width] = i;
+    }
+    for (j, cell) in matrix.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            let mut value = (matrix[(i - 1) * width + j] + 1)
+                .min(matrix[i * width + j - 1] + 1)
+                .min(matrix[(i - 1) * width + j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(matrix[(i - 2) * width + j - 2] + 1);
+            }
+
+            matrix[i * width + j] = value;
+        }
+    }
+
+    matrix[a.len() * width + b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typos_match() {
+        let pattern = Pattern::new("vscod", false);
+        assert!(pattern.score("vscode").is_some());
+
+        let pattern = Pattern::new("nodjs", false);
+        assert!(pattern.score("nodejs").is_some());
+
+        let pattern = Pattern::new("pyhton", false);
+        assert!(pattern.score("python").is_some());
+        assert!(pattern.score("rust").is_none());
+    }
+
+    #[test]
+    fn test_ranking() {
+        let pattern = Pattern::new("git", false);
+
+        let exact = pattern.score("git").unwrap();
+        let prefix = pattern.score("github-cli").unwrap();
+        let substring = pattern.score("lazygit").unwrap();
+        let subsequence = pattern.score("grit").unwrap();
+
+        assert_eq!(exact, EXACT);
+        assert!(exact > prefix);
+        assert!(prefix > substring);
+        assert!(substring > subsequence);
+    }
+
+    #[test]
+    fn test_score_words() {
+        let pattern = Pattern::new("json dif", false);
+        assert!(pattern
+            .score_words("A command-line JSON diff tool")
+            .is_some());
+
+        let pattern = Pattern::new("pdf", false);
+        assert!(pattern.score_words("Lightweight PDF viewer").is_some());
+        assert!(pattern
+            .score_words("A program that produces diffs of files")
+            .is_none());
+    }
+
+    #[test]
+    fn test_case_sensitivity() {
+        assert_eq!(Pattern::new("Git", false).score("git"), Some(EXACT));
+        assert_ne!(Pattern::new("Git", true).score("git"), Some(EXACT));
+    }
+}
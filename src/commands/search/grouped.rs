@@ -0,0 +1,151 @@
+//! Collapsing search results from every bucket into one result per app
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    output::sectioned::{Children, Section, Text},
+    wrappers::version::Version,
+};
+
+use super::MatchedManifest;
+
+#[derive(Debug, Serialize)]
+/// A bucket that provides an app, and the version it provides
+pub struct BucketVersion {
+    bucket: String,
+    version: String,
+    newest: bool,
+    installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    add_command: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+/// An app, and every bucket it was found in
+pub struct GroupedApp {
+    name: String,
+    score: u32,
+    buckets: Vec<BucketVersion>,
+    #[serde(skip)]
+    exact_match: bool,
+}
+
+impl GroupedApp {
+    /// Group matches from every bucket by app name, ordered by relevance
+    pub fn group(
+        buckets: HashMap<String, Vec<MatchedManifest>>,
+        add_command: impl Fn(&str) -> Option<String>,
+    ) -> Vec<Self> {
+        let apps = buckets
+            .into_values()
+            .flatten()
+            .into_group_map_by(|matched| matched.entry.name.clone());
+
+        let mut apps = apps
+            .into_iter()
+            .map(|(name, matches)| {
+                let score = matches
+                    .iter()
+                    .map(|matched| matched.criteria.score)
+                    .max()
+                    .unwrap_or_default();
+                let exact_match = matches.iter().any(|matched| matched.exact_match);
+
+                let newest = newest_versions(&matches);
+
+                let buckets = matches
+                    .into_iter()
+                    .zip(newest)
+                    .map(|(matched, newest)| BucketVersion {
+                        add_command: add_command(&matched.entry.bucket),
+                        bucket: matched.entry.bucket,
+                        version: matched.entry.version,
+                        newest,
+                        installed: matched.installed,
+                    })
+                    .sorted_by(|a, b| a.bucket.cmp(&b.bucket))
+                    .collect();
+
+                Self {
+                    name,
+                    score,
+                    buckets,
+                    exact_match,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        apps.sort_by(|a, b| {
+            b.exact_match
+                .cmp(&a.exact_match)
+                .then_with(|| b.score.cmp(&a.score))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        apps
+    }
+
+    pub fn to_section(&self) -> Section<Text<String>> {
+        let title = if self.exact_match {
+            console::style(&self.name).bold().to_string()
+        } else {
+            self.name.clone()
+        };
+
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|bucket| {
+                let newest_text = if bucket.newest { " [newest]" } else { "" };
+                let installed_text = if bucket.installed { " [installed]" } else { "" };
+                let not_added_text = if bucket.add_command.is_some() {
+                    " (bucket not added)"
+                } else {
+                    ""
+                };
+
+                Text::new(format!(
+                    "{}{}: {}{newest_text}{installed_text}{not_added_text}",
+                    crate::output::WHITESPACE,
+                    bucket.bucket,
+                    bucket.version,
+                ))
+            })
+            .collect_vec();
+
+        Section::new(Children::from(buckets)).with_title(title)
+    }
+}
+
+/// Mark which of the matches provide the newest version
+///
+/// Nothing is marked if the app is only in one bucket, every bucket provides the same version,
+/// or the versions can't be compared
+fn newest_versions(matches: &[MatchedManifest]) -> Vec<bool> {
+    let versions = matches
+        .iter()
+        .map(|matched| matched.entry.version.as_str())
+        .collect_vec();
+
+    versions
+        .iter()
+        .map(|version| {
+            let orderings = versions
+                .iter()
+                .map(|other| compare_versions(version, other))
+                .collect_vec();
+
+            orderings
+                .iter()
+                .all(|ordering| matches!(ordering, Some(Ordering::Greater | Ordering::Equal)))
+                && orderings.contains(&Some(Ordering::Greater))
+        })
+        .collect()
+}
+
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Version::new(a).partial_cmp(&Version::new(b))
+}
@@ -0,0 +1,235 @@
+//! Persistent search index
+//!
+//! Parsing every manifest in every bucket is the slowest part of searching.
+//! The index stores a summary of each bucket's manifests in the Scoop cache directory,
+//! keyed by the bucket's latest commit, so buckets are only parsed again when they change.
+//! Buckets with uncommitted changes are parsed instead, as their commit doesn't describe their manifests.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sprinkles::{
+    buckets::Bucket,
+    contexts::ScoopContext,
+    packages::{Manifest, MergeDefaults},
+    Architecture,
+};
+
+/// The index format version
+///
+/// Bump this whenever [`Entry`] changes, to invalidate existing indexes
+const INDEX_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A summary of a manifest, containing only the fields used for searching
+pub struct Entry {
+    /// The name of the package
+    pub name: String,
+    /// The bucket the package is in
+    pub bucket: String,
+    /// The version of the package
+    pub version: String,
+    /// The description of the package
+    pub description: Option<String>,
+    /// The homepage of the package
+    pub homepage: Option<String>,
+    /// The license of the package
+    pub license: Option<String>,
+    /// The binaries the package provides for the indexed architecture
+    pub bins: Vec<String>,
+    /// The package's notes
+    pub notes: Option<String>,
+}
+
+impl Entry {
+    #[must_use]
+    /// Summarize a manifest for the given architecture
+    pub fn from_manifest(manifest: &Manifest, arch: Architecture) -> Self {
+        Self::new(
+            unsafe { manifest.name() }.to_string(),
+            unsafe { manifest.bucket() }.to_string(),
+            manifest,
+            arch,
+        )
+    }
+
+    #[must_use]
+    /// Summarize a manifest for the given architecture, which was read outside of a [`Bucket`]
+    pub fn new(name: String, bucket: String, manifest: &Manifest, arch: Architecture) -> Self {
+        Self {
+            name,
+            bucket,
+            version: manifest.version.to_string(),
+            description: manifest.description.clone(),
+            homepage: manifest.homepage.clone(),
+            license: manifest.license.as_ref().map(ToString::to_string),
+            bins: manifest
+                .architecture
+                .merge_default(manifest.install_config.clone(), arch)
+                .bin
+                .map(|b| b.to_vec())
+                .unwrap_or_default(),
+            notes: manifest.notes.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The search index for a single bucket
+pub struct BucketIndex {
+    version: u32,
+    commit: String,
+    arch: String,
+    entries: Vec<Entry>,
+}
+
+impl BucketIndex {
+    /// The directory all bucket indexes are stored in
+    pub fn dir(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.cache_path().join("sfsu-search-index")
+    }
+
+    fn path(ctx: &impl ScoopContext, bucket: &str) -> PathBuf {
+        Self::dir(ctx).join(format!("{bucket}.json"))
+    }
+
+    /// Load the entries for a bucket from the index, re-parsing the bucket if its index is missing or outdated
+    ///
+    /// Passing `rebuild` will always re-parse the bucket
+    ///
+    /// # Errors
+    /// - The bucket's manifests could not be listed
+    pub fn entries(
+        ctx: &impl ScoopContext,
+        bucket: &Bucket,
+        arch: Architecture,
+        rebuild: bool,
+    ) -> anyhow::Result<Vec<Entry>> {
+        Self::cached(
+            ctx,
+            &bucket.name(),
+            Self::latest_commit(bucket),
+            arch,
+            rebuild,
+            || has_local_changes(&bucket.name(), bucket.path()),
+            || Self::parse(bucket, arch),
+        )
+    }
+
+    /// Load entries from the index for the named bucket at the given commit,
+    /// calling `parse` and updating the index if it is missing or outdated
+    ///
+    /// Passing `rebuild` will always call `parse`.
+    /// `local_changes` is only called once the commit is known, and if it returns `true`
+    /// the index is neither used nor written, as the bucket's manifests differ from its commit
+    ///
+    /// # Errors
+    /// - `parse` failed
+    pub fn cached(
+        ctx: &impl ScoopContext,
+        name: &str,
+        commit: Option<String>,
+        arch: Architecture,
+        rebuild: bool,
+        local_changes: impl FnOnce() -> bool,
+        parse: impl FnOnce() -> anyhow::Result<Vec<Entry>>,
+    ) -> anyhow::Result<Vec<Entry>> {
+        let path = Self::path(ctx, name);
+
+        let Some(commit) = commit else {
+            // Buckets that aren't git repositories can't be indexed
+            debug!("Not indexing bucket {name}, as its latest commit could not be read");
+            return parse();
+        };
+
+        if !rebuild {
+            match Self::read(&path) {
+                Ok(index)
+                    if index.version == INDEX_VERSION
+                        && index.commit == commit
+                        && index.arch == arch.to_string() =>
+                {
+                    if local_changes() {
+                        debug!("Not using search index for bucket {name}, as it has local changes");
+                        return parse();
+                    }
+
+                    debug!("Using search index for bucket {name}");
+                    return Ok(index.entries);
+                }
+                Ok(_) => debug!("Search index for bucket {name} is outdated"),
+                Err(err) => debug!("Failed to read search index for bucket {name}: {err:?}"),
+            }
+        }
+
+        let index = Self {
+            version: INDEX_VERSION,
+            commit,
+            arch: arch.to_string(),
+            entries: parse()?,
+        };
+
+        // Entries parsed from local changes would be used for the commit once the changes are reverted
+        if local_changes() {
+            debug!("Not indexing bucket {name}, as it has local changes");
+            return Ok(index.entries);
+        }
+
+        // A failure to write the index should never stop a search
+        if let Err(err) = index.write(&path) {
+            warn!("Failed to write search index for bucket {name}: {err}");
+        }
+
+        Ok(index.entries)
+    }
+
+    /// Parse every manifest in a bucket, bypassing the index
+    ///
+    /// # Errors
+    /// - The bucket's manifests could not be listed
+    pub fn parse(bucket: &Bucket, arch: Architecture) -> anyhow::Result<Vec<Entry>> {
+        Ok(bucket
+            .list_packages()?
+            .iter()
+            .map(|manifest| Entry::from_manifest(manifest, arch))
+            .collect())
+    }
+
+    fn latest_commit(bucket: &Bucket) -> Option<String> {
+        let repo = bucket.open_repo().ok()?;
+        let commit = repo.latest_commit().ok()?;
+
+        Some(commit.id().to_string())
+    }
+
+    fn read(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+}
+
+/// Check if a bucket's tracked manifests have uncommitted changes
+///
+/// If the working tree can't be read, the bucket's commit is trusted, so the index is still used
+fn has_local_changes(name: &str, path: &Path) -> bool {
+    crate::git::has_local_changes(path).unwrap_or_else(|err| {
+        debug!("Failed to read the working tree of bucket {name}: {err}");
+        false
+    })
+}
@@ -0,0 +1,287 @@
+//! Interactive package picker
+//!
+//! The picker is drawn to stderr, so that the chosen package can be piped from stdout.
+
+use std::io::stderr;
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use rayon::prelude::*;
+
+use super::{index::Entry, Args, Field, InstalledApps, MatchCriteria};
+
+const LABEL_STYLE: Style = Style::new().add_modifier(Modifier::BOLD);
+
+/// Sets up the terminal for the picker, and restores it when dropped,
+/// so that it is restored even if the picker fails or panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> std::io::Result<Self> {
+        enable_raw_mode()?;
+
+        // Created before entering the alternate screen, so raw mode is still disabled if that fails
+        let guard = Self;
+        stderr().execute(EnterAlternateScreen)?;
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Both steps are always attempted, as there is no way to report a failure from here
+        _ = disable_raw_mode();
+        _ = stderr().execute(LeaveAlternateScreen);
+    }
+}
+
+pub struct Picker<'a> {
+    args: &'a Args,
+    entries: Vec<Entry>,
+    installed_apps: InstalledApps,
+    fields: Vec<Field>,
+    query: String,
+    results: Vec<usize>,
+    invalid_query: bool,
+    state: ListState,
+}
+
+impl<'a> Picker<'a> {
+    /// Create a picker over the given entries, initially filtered by `query`
+    pub fn new(
+        args: &'a Args,
+        entries: Vec<Entry>,
+        installed_apps: InstalledApps,
+        query: String,
+    ) -> Self {
+        let mut picker = Self {
+            fields: args.fields(),
+            args,
+            entries,
+            installed_apps,
+            query,
+            results: vec![],
+            invalid_query: false,
+            state: ListState::default(),
+        };
+
+        picker.filter();
+
+        picker
+    }
+
+    /// Run the picker, returning the chosen entry, or [`None`] if it was cancelled
+    ///
+    /// # Errors
+    /// - The terminal could not be set up or drawn to
+    pub fn run(mut self) -> anyhow::Result<Option<Entry>> {
+        let _guard = TerminalGuard::enter()?;
+
+        self.event_loop()
+    }
+
+    fn event_loop(&mut self) -> anyhow::Result<Option<Entry>> {
+        let mut terminal = Terminal::new(CrosstermBackend::new(stderr()))?;
+
+        loop {
+            terminal.draw(|frame| self.ui(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(None)
+                }
+                KeyCode::Enter => return Ok(self.selected().cloned()),
+                KeyCode::Up => self.move_selection(false),
+                KeyCode::Down => self.move_selection(true),
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.filter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.filter();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        let selected = self.state.selected()?;
+
+        self.results
+            .get(selected)
+            .map(|index| &self.entries[*index])
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+
+        let next = if down {
+            (selected + 1).min(self.results.len().saturating_sub(1))
+        } else {
+            selected.saturating_sub(1)
+        };
+
+        self.state.select(Some(next));
+    }
+
+    /// Filter the entries by the current query, ordered by relevance
+    fn filter(&mut self) {
+        if self.query.is_empty() {
+            self.results = (0..self.entries.len()).collect();
+            self.invalid_query = false;
+        } else {
+            // Keep showing the previous results while the query is invalid (i.e an unfinished regex)
+            let Ok(matcher) = self.args.matcher(&self.query) else {
+                self.invalid_query = true;
+                return;
+            };
+
+            let mut results = self
+                .entries
+                .par_iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    let criteria = MatchCriteria::matches(entry, &matcher, &self.fields);
+
+                    criteria.any().then_some((index, criteria.score))
+                })
+                .collect::<Vec<_>>();
+
+            results.par_sort_by(|(a_index, a_score), (b_index, b_score)| {
+                b_score.cmp(a_score).then_with(|| {
+                    self.entries[*a_index]
+                        .name
+                        .cmp(&self.entries[*b_index].name)
+                })
+            });
+
+            self.results = results.into_iter().map(|(index, _)| index).collect();
+            self.invalid_query = false;
+        }
+
+        self.state.select((!self.results.is_empty()).then_some(0));
+    }
+
+    fn ui(&mut self, frame: &mut Frame<'_>) {
+        let [input_area, body_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body_area);
+
+        let input_title = if self.invalid_query {
+            "Search (invalid pattern)".to_string()
+        } else {
+            format!("Search ({} results)", self.results.len())
+        };
+
+        frame.render_widget(
+            Paragraph::new(self.query.as_str())
+                .block(Block::default().title(input_title).borders(Borders::ALL)),
+            input_area,
+        );
+
+        let cursor_offset = u16::try_from(self.query.chars().count()).unwrap_or(u16::MAX);
+        frame.set_cursor_position((
+            input_area.x.saturating_add(1).saturating_add(cursor_offset),
+            input_area.y + 1,
+        ));
+
+        let items = self.results.iter().map(|index| {
+            let entry = &self.entries[*index];
+
+            let mut spans = vec![
+                Span::raw(format!("{}/{}", entry.bucket, entry.name)),
+                Span::styled(
+                    format!(" ({})", entry.version),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ];
+
+            if self.installed_apps.contains(entry) {
+                spans.push(Span::styled(
+                    " [installed]",
+                    Style::default().fg(Color::Green),
+                ));
+            }
+
+            ListItem::new(Line::from(spans))
+        });
+
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .block(
+                    Block::default()
+                        .title_bottom("Enter to select | Esc to cancel")
+                        .borders(Borders::ALL),
+                ),
+            list_area,
+            &mut self.state,
+        );
+
+        let preview = self.selected().map(preview).unwrap_or_default();
+
+        frame.render_widget(
+            Paragraph::new(preview)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().title("Info").borders(Borders::ALL)),
+            preview_area,
+        );
+    }
+}
+
+/// Describe an entry, like `app info`
+fn preview(entry: &Entry) -> Text<'_> {
+    let fields = [
+        ("Name", Some(entry.name.clone())),
+        ("Bucket", Some(entry.bucket.clone())),
+        ("Version", Some(entry.version.clone())),
+        ("Description", entry.description.clone()),
+        ("Homepage", entry.homepage.clone()),
+        ("License", entry.license.clone()),
+        (
+            "Binaries",
+            (!entry.bins.is_empty()).then(|| entry.bins.join(", ")),
+        ),
+        ("Notes", entry.notes.clone()),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(label, value)| {
+            let value = value?;
+
+            Some(Line::from(vec![
+                Span::styled(format!("{label}: "), LABEL_STYLE),
+                Span::raw(value),
+            ]))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
@@ -0,0 +1,199 @@
+//! Searching known buckets that haven't been added
+//!
+//! Known buckets are shallow cloned into the Scoop cache directory the first time they are searched,
+//! cloned again once they are a week old, and indexed like any other bucket.
+
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+    time::Duration,
+};
+
+use sprinkles::{
+    contexts::ScoopContext, git::implementations::gix, packages::Manifest, Architecture,
+};
+
+use super::index::{BucketIndex, Entry};
+
+/// How old a known bucket's clone can be before it is cloned again
+const MAX_CLONE_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+/// A known bucket that hasn't been added
+pub struct KnownBucket {
+    /// The name of the bucket
+    pub name: String,
+    url: String,
+    path: PathBuf,
+}
+
+impl KnownBucket {
+    /// The directory known buckets are cloned into
+    pub fn dir(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.cache_path().join("sfsu-known-buckets")
+    }
+
+    /// List the known buckets that haven't been added, optionally only the one with the given name
+    pub fn list_not_added(ctx: &impl ScoopContext, name: Option<&str>) -> Vec<Self> {
+        let buckets_path = ctx.buckets_path();
+        let dir = Self::dir(ctx);
+
+        ctx.known_buckets()
+            .into_iter()
+            .map(|(name, url)| ((*name).to_string(), (*url).to_string()))
+            .filter(|(known_name, _)| name.is_none_or(|name| name == *known_name))
+            .filter(|(known_name, _)| !buckets_path.join(known_name).exists())
+            .map(|(name, url)| Self {
+                path: dir.join(&name),
+                name,
+                url,
+            })
+            .collect()
+    }
+
+    #[must_use]
+    /// The command to run to add the bucket
+    pub fn add_command(name: &str) -> String {
+        format!("sfsu bucket add {name}")
+    }
+
+    /// Clone the given buckets if they haven't been cloned yet, or their clones are outdated
+    ///
+    /// Passing `refresh` will replace every existing clone with a fresh one
+    ///
+    /// # Errors
+    /// - A bucket could not be removed or cloned
+    pub async fn clone_all(buckets: &[Self], refresh: bool) -> anyhow::Result<()> {
+        let missing = buckets
+            .iter()
+            .filter(|bucket| refresh || bucket.is_outdated())
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let root = prodash::tree::Root::new();
+        let handle = crate::progress::render::LineRenderer::run(root.clone(), true);
+
+        for bucket in missing {
+            // Clone into a temporary directory first, so that a failed or interrupted clone
+            // is never mistaken for a complete one on later runs
+            let partial = bucket
+                .path
+                .with_file_name(format!("{}.partial", bucket.name));
+
+            if partial.exists() {
+                std::fs::remove_dir_all(&partial)?;
+            }
+
+            let clone_progress =
+                root.add_child_with_id(format!("Cloning {} bucket", bucket.name), *b"REPO");
+
+            if let Err(err) = shallow_clone(&bucket.url, &partial, clone_progress) {
+                if partial.exists() {
+                    std::fs::remove_dir_all(&partial)?;
+                }
+
+                // An outdated clone is still better than failing the search, i.e when offline
+                if !refresh && bucket.path.exists() {
+                    warn!("Failed to refresh known bucket {}: {err:#}", bucket.name);
+                    continue;
+                }
+
+                return Err(err);
+            }
+
+            if bucket.path.exists() {
+                std::fs::remove_dir_all(&bucket.path)?;
+            }
+
+            std::fs::rename(&partial, &bucket.path)?;
+        }
+
+        handle.await?;
+
+        Ok(())
+    }
+
+    /// Load the bucket's entries, using the search index if possible
+    ///
+    /// # Errors
+    /// - The bucket's manifests could not be listed
+    pub fn entries(
+        &self,
+        ctx: &impl ScoopContext,
+        arch: Architecture,
+        rebuild: bool,
+    ) -> anyhow::Result<Vec<Entry>> {
+        // Known buckets are only ever cloned by sfsu, so they never have local changes
+        BucketIndex::cached(
+            ctx,
+            &self.name,
+            self.head(),
+            arch,
+            rebuild,
+            || false,
+            || self.parse(arch),
+        )
+    }
+
+    /// Check if the bucket hasn't been cloned, or was cloned too long ago
+    fn is_outdated(&self) -> bool {
+        std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age > MAX_CLONE_AGE)
+    }
+
+    fn head(&self) -> Option<String> {
+        let repo = gix::open(&self.path).ok()?;
+        let id = repo.head_id().ok()?;
+
+        Some(id.to_string())
+    }
+
+    fn parse(&self, arch: Architecture) -> anyhow::Result<Vec<Entry>> {
+        // Most buckets keep their manifests in a `bucket` directory, but some keep them in the root
+        let nested = self.path.join("bucket");
+        let dir = if nested.exists() {
+            nested
+        } else {
+            self.path.clone()
+        };
+
+        let entries = std::fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+
+                match Manifest::from_path(&path) {
+                    Ok(manifest) => Some(Entry::new(name, self.name.clone(), &manifest, arch)),
+                    Err(err) => {
+                        debug!("Failed to parse manifest {}: {err}", path.display());
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(entries)
+    }
+}
+
+/// Clone only the latest commit of a bucket, as the history of known buckets is never used
+fn shallow_clone(url: &str, path: &Path, progress: prodash::tree::Item) -> anyhow::Result<()> {
+    let interrupt = AtomicBool::new(false);
+
+    let mut prepare = gix::prepare_clone(url, path)?
+        .with_shallow(gix::remote::fetch::Shallow::DepthAtRemote(NonZeroU32::MIN));
+    let (mut checkout, _) =
+        prepare.fetch_then_checkout(progress.add_child("Fetching"), &interrupt)?;
+    checkout.main_worktree(progress.add_child("Checking out"), &interrupt)?;
+
+    Ok(())
+}
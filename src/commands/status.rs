@@ -21,6 +21,37 @@ use crate::{
     },
 };
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+/// The problems found, which are used as the exit code with `--exit-code`
+///
+/// Each problem has its own bit, so that combinations of problems are distinguishable
+struct Problems(u8);
+
+impl Problems {
+    const NONE: Self = Self(0);
+    const SCOOP_OUTDATED: Self = Self(1 << 1);
+    const BUCKETS_OUTDATED: Self = Self(1 << 2);
+    const APPS_OUTDATED: Self = Self(1 << 3);
+    const MISSING_DEPENDENCIES: Self = Self(1 << 4);
+    const FAILED_INSTALLS: Self = Self(1 << 5);
+
+    fn when(self, condition: bool) -> Self {
+        if condition {
+            self
+        } else {
+            Self::NONE
+        }
+    }
+}
+
+impl std::ops::BitOr for Problems {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
 #[derive(Debug, Copy, Clone, ValueEnum, ListVariants)]
 enum Command {
     Scoop,
@@ -42,6 +73,28 @@ pub struct Args {
 
     #[clap(short = 'H', long, help = "Ignore held packages")]
     ignore_held: bool,
+
+    #[clap(
+        long,
+        help = "Check every bucket for a newer version, not just the bucket each app was installed from"
+    )]
+    all_buckets: bool,
+
+    #[clap(
+        long,
+        help = "Exit with a non-zero code if anything needs attention",
+        long_help = "Exit with a non-zero code if anything needs attention. \
+            The exit code is the sum of the following codes, one for each problem found:
+
+  2   Scoop is out of date
+  4   Bucket(s) are out of date
+  8   App(s) are out of date
+  16  App(s) have missing dependencies
+  32  App(s) failed to install
+
+An exit code of 1 means that status could not be checked"
+    )]
+    exit_code: bool,
 }
 
 impl super::Command for Args {
@@ -66,7 +119,7 @@ impl super::Command for Args {
             async move {
                 let mut output = String::new();
 
-                match command {
+                let problems = match command {
                     Command::Scoop => this.handle_scoop(ctx, &value, &mut output).await?,
                     Command::Buckets => this.handle_buckets(ctx, &value, &mut output)?,
                     Command::Apps => this.handle_packages(ctx, &value, &mut output)?,
@@ -74,11 +127,14 @@ impl super::Command for Args {
 
                 pb.inc(1);
 
-                anyhow::Ok(output)
+                anyhow::Ok((output, problems))
             }
         });
 
-        let outputs = futures::future::try_join_all(outputs).await?;
+        let (outputs, problems): (Vec<_>, Vec<_>) = futures::future::try_join_all(outputs)
+            .await?
+            .into_iter()
+            .unzip();
 
         pb.finish_and_clear();
 
@@ -91,6 +147,16 @@ impl super::Command for Args {
             }
         }
 
+        if self.exit_code {
+            let problems = problems
+                .into_iter()
+                .fold(Problems::NONE, |acc, problems| acc | problems);
+
+            if problems != Problems::NONE {
+                std::process::exit(problems.0.into());
+            }
+        }
+
         Ok(())
     }
 }
@@ -101,12 +167,13 @@ impl Args {
         ctx: &impl ScoopContext,
         value: &Mutex<Value>,
         output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Problems> {
         let is_outdated = ctx.outdated().await?;
+        let problems = Problems::SCOOP_OUTDATED.when(is_outdated);
 
         if self.json {
             value.lock()["scoop"] = serde_json::to_value(is_outdated)?;
-            return Ok(());
+            return Ok(problems);
         } else if is_outdated {
             writeln!(
                 output,
@@ -120,7 +187,7 @@ impl Args {
             writeln!(output, "Scoop app is up to date.")?;
         }
 
-        Ok(())
+        Ok(problems)
     }
 
     fn handle_buckets(
@@ -128,7 +195,7 @@ impl Args {
         ctx: &impl ScoopContext,
         value: &Mutex<Value>,
         output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Problems> {
         let buckets = Bucket::list_all(ctx)?;
 
         // Handle buckets
@@ -146,9 +213,11 @@ impl Args {
                 })
                 .collect::<Vec<_>>();
 
+            let problems = Problems::BUCKETS_OUTDATED.when(!outdated_buckets.is_empty());
+
             if self.json {
                 value.lock()["buckets"] = serde_json::to_value(&outdated_buckets)?;
-                return Ok(());
+                return Ok(problems);
             }
 
             if outdated_buckets.is_empty() {
@@ -160,6 +229,8 @@ impl Args {
 
                 writeln!(output, "{section}")?;
             }
+
+            Ok(problems)
         } else {
             let buckets_outdated = buckets.par_iter().any(|bucket| {
                 bucket.outdated().unwrap_or_else(|err| {
@@ -185,9 +256,9 @@ impl Args {
             } else {
                 writeln!(output, "All buckets up to date.")?;
             }
-        }
 
-        Ok(())
+            Ok(Problems::BUCKETS_OUTDATED.when(buckets_outdated))
+        }
     }
 
     fn handle_packages(
@@ -195,21 +266,27 @@ impl Args {
         ctx: &impl ScoopContext,
         value: &Mutex<Value>,
         output: &mut dyn Write,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Problems> {
         let apps = install::Manifest::list_all_unchecked(ctx)?;
 
         debug!("Checking {} apps", apps.len());
 
+        let all_buckets = if self.all_buckets {
+            Bucket::list_all(ctx)?
+        } else {
+            vec![]
+        };
+
         let mut invalid_apps = apps
             .par_iter()
             .flat_map(|app| -> anyhow::Result<Info> {
                 if let Some(bucket) = &app.bucket {
                     let local_manifest = app.get_manifest(ctx)?;
-                    // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
+                    let bucket_name = bucket;
                     let bucket = Bucket::from_name(ctx, bucket)?;
 
                     match Info::from_manifests(ctx, &local_manifest, &bucket) {
-                        Ok(info) => Ok(info),
+                        Ok(info) => Ok(info.with_alternative(&all_buckets, bucket_name)),
                         Err(err) => {
                             error!(
                                 "Failed to get status for {}: {:?}",
@@ -238,15 +315,29 @@ impl Args {
                 };
 
                 // Filter out apps that are okay
-                info_exists || missing_deps || app.current != app.available
+                info_exists
+                    || missing_deps
+                    || app.change.is_some()
+                    || app.alternative_bucket.is_some()
             })
             .collect::<Vec<_>>();
 
         invalid_apps.dedup();
 
+        let problems = Problems::APPS_OUTDATED.when(
+            invalid_apps
+                .iter()
+                .any(|app| app.change.is_some() || app.alternative_bucket.is_some()),
+        ) | Problems::MISSING_DEPENDENCIES.when(
+            invalid_apps
+                .iter()
+                .any(|app| !app.missing_dependencies.is_empty()),
+        ) | Problems::FAILED_INSTALLS
+            .when(invalid_apps.iter().any(Info::install_failed));
+
         if self.json {
             value.lock()["packages"] = serde_json::to_value(&invalid_apps)?;
-            return Ok(());
+            return Ok(problems);
         }
 
         if invalid_apps.is_empty() {
@@ -288,6 +379,6 @@ impl Args {
             // }
         }
 
-        Ok(())
+        Ok(problems)
     }
 }
@@ -1,35 +1,189 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::Context;
-use clap::Parser;
+use chrono::{DateTime, FixedOffset, Local};
+use clap::{builder::ArgPredicate, Parser};
 use itertools::Itertools;
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use serde::Serialize;
 
 use sprinkles::{
-    buckets::{self, Bucket},
+    buckets::Bucket,
     config::Scoop as ScoopConfig,
     contexts::ScoopContext,
-    git::{self, Repo},
+    git::Repo,
+    packages::models::install,
     progress::{
         indicatif::{MultiProgress, ProgressBar, ProgressFinish, ProgressStyle},
         style, Message, ProgressOptions,
     },
 };
 
-use crate::output::sectioned::{Children, Section};
+use crate::{
+    errors::retry_with_backoff,
+    journal::{self, Journal},
+    output::sectioned::{Children, Section, Sections},
+    wrappers::duration::NicerDuration,
+};
+
+mod changelog;
+mod dry_run;
 
 #[derive(Debug, Clone, Parser)]
 /// Update Scoop and Scoop buckets
 pub struct Args {
-    #[clap(short, long, help = "Show commit messages for each update")]
+    #[clap(
+        value_name = "BUCKETS",
+        help = "The buckets to update. Updates all buckets if not provided"
+    )]
+    buckets: Vec<String>,
+
+    #[clap(
+        short,
+        long,
+        help = "Show commit messages for each update. Implied by --changelog-scope and --changelog-format",
+        default_value_ifs = [
+            ("changelog_scope", ArgPredicate::IsPresent, "true"),
+            ("changelog_format", ArgPredicate::IsPresent, "true"),
+        ]
+    )]
     changelog: bool,
+
+    #[clap(long, help = "Fetch and show incoming changes, without applying them")]
+    dry_run: bool,
+
+    #[clap(long, help = "Don't update Scoop itself")]
+    skip_scoop: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "The maximum number of buckets to update in parallel. Defaults to the number of CPUs"
+    )]
+    jobs: Option<usize>,
+
+    #[clap(
+        long,
+        default_value_t = 3,
+        help = "The number of times to retry an update that failed due to a network error"
+    )]
+    retries: u32,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value_t,
+        help = "Which commits to show in the changelog"
+    )]
+    changelog_scope: changelog::Scope,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value_t,
+        help = "The format to show the changelog in"
+    )]
+    changelog_format: changelog::Format,
+
+    #[clap(
+        long,
+        value_name = "DURATION",
+        help = "Only update if the last update was longer ago than the given duration (e.g. 6h, 1d)"
+    )]
+    if_older_than: Option<NicerDuration>,
+
+    #[clap(from_global)]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    changelog: Vec<&'a changelog::Entry>,
+    failed: BTreeMap<&'a str, String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPreview<'a> {
+    previews: Vec<&'a dry_run::Preview>,
+    failed: BTreeMap<&'a str, String>,
+}
+
+/// A repository that was updated
+struct Pulled {
+    /// The commit the repository was at before the update
+    previous_head: String,
+    changelog: Vec<changelog::Entry>,
+}
+
+/// The result of updating a repository
+enum Outcome {
+    Updated(Pulled),
+    UpToDate,
+    Failed(anyhow::Error),
+}
+
+impl Outcome {
+    fn from_result(result: anyhow::Result<Option<Pulled>>) -> Self {
+        match result {
+            Ok(Some(pulled)) => Self::Updated(pulled),
+            Ok(None) => Self::UpToDate,
+            Err(e) => Self::Failed(e),
+        }
+    }
+
+    fn changelog(&self) -> &[changelog::Entry] {
+        match self {
+            Self::Updated(pulled) => &pulled.changelog,
+            _ => &[],
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Updated(_) => write!(f, "✅ Updated"),
+            Self::UpToDate => write!(f, "✅ Up to date"),
+            Self::Failed(e) => write!(f, "❌ Failed: {e:#}"),
+        }
+    }
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
+        if let Some(max_age) = self.if_older_than {
+            if let Some(age) = Self::since_last_update() {
+                if age < *max_age {
+                    if !self.json {
+                        println!(
+                            "Skipping update, as the last update was {} ago",
+                            NicerDuration::from(age)
+                        );
+                    }
+
+                    return Ok(());
+                }
+            }
+        }
+
         let progress_style = style(Some(ProgressOptions::Hide), Some(Message::suffix()));
 
-        let buckets = Bucket::list_all(ctx)?;
+        let buckets = if self.buckets.is_empty() {
+            Bucket::list_all(ctx)?
+        } else {
+            self.buckets
+                .iter()
+                .map(|name| {
+                    Bucket::from_name(ctx, name).with_context(|| format!("missing bucket {name}"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
 
         let longest_bucket_name = buckets
             .iter()
@@ -37,11 +191,18 @@ impl super::Command for Args {
             .max()
             .unwrap_or(0);
 
-        // Force checkout to the config's branch
-        _ = ctx.outdated().await?;
+        if self.dry_run {
+            return self.preview(ctx, &buckets);
+        }
+
+        let scoop_outcome = if self.skip_scoop {
+            None
+        } else {
+            // Force checkout to the config's branch
+            _ = ctx.outdated().await?;
 
-        let scoop_changelog =
-            self.update_scoop(ctx, longest_bucket_name, progress_style.clone())?;
+            Some(self.update_scoop(ctx, longest_bucket_name, progress_style.clone()))
+        };
 
         let mp = MultiProgress::new();
 
@@ -62,33 +223,89 @@ impl super::Command for Args {
             })
             .collect_vec();
 
-        let bucket_changelogs = self.update_buckets(ctx, &outdated_buckets)?;
+        let bucket_outcomes = self.update_buckets(ctx, &outdated_buckets)?;
 
-        let mut scoop_config = ScoopConfig::load()?;
-        scoop_config.update_last_update_time();
-        scoop_config.save()?;
+        let outcomes = scoop_outcome
+            .map(|outcome| (Cow::Borrowed(journal::SCOOP), outcome))
+            .into_iter()
+            .chain(bucket_outcomes)
+            .collect_vec();
 
-        if self.changelog {
-            println!();
-            if let Some(scoop_changelog) = scoop_changelog {
-                let scoop_changelog =
-                    Section::new(Children::from(scoop_changelog)).with_title("Scoop changes:");
+        let failed = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+            .count();
+
+        let mut journal = Journal::load(ctx)?;
+        journal.record(
+            outcomes
+                .iter()
+                .filter_map(|(name, outcome)| match outcome {
+                    Outcome::Updated(pulled) => {
+                        Some((name.to_string(), pulled.previous_head.clone()))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        );
+        journal.save(ctx)?;
+
+        // Only record the update if everything was updated, so that a failed update is retried next time
+        if failed == 0 && !self.skip_scoop && self.buckets.is_empty() {
+            let mut scoop_config = ScoopConfig::load()?;
+            scoop_config.update_last_update_time();
+            scoop_config.save()?;
+        }
+
+        let installed_apps = if self.changelog_scope == changelog::Scope::Installed {
+            Self::installed_apps(ctx)?
+        } else {
+            vec![]
+        };
+        let installed_apps = dry_run::installed_by_bucket(&installed_apps);
+
+        let entries = outcomes
+            .iter()
+            .flat_map(|(_, outcome)| outcome.changelog())
+            .filter(|entry| match self.changelog_scope {
+                changelog::Scope::All => true,
+                changelog::Scope::Installed => entry.touches_installed(&installed_apps),
+            })
+            .collect_vec();
 
-                print!("{scoop_changelog}");
+        if self.json {
+            let output = JsonOutput {
+                changelog: entries,
+                failed: outcomes
+                    .iter()
+                    .filter_map(|(name, outcome)| match outcome {
+                        Outcome::Failed(e) => Some((name.as_ref(), format!("{e:#}"))),
+                        _ => None,
+                    })
+                    .collect(),
             };
 
-            for bucket_changelog in bucket_changelogs {
-                let (name, changelog) = bucket_changelog;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            if self.changelog && !entries.is_empty() {
+                println!();
+                changelog::print(&entries, self.changelog_format);
+            }
 
-                if changelog.is_empty() {
-                    continue;
-                }
+            let summary = outcomes
+                .iter()
+                .map(|(name, outcome)| format!("{name:<longest_bucket_name$} {outcome}"))
+                .collect_vec();
 
-                let changelog =
-                    Section::new(Children::from(changelog)).with_title(format!("{name} changes:"));
+            println!();
+            print!(
+                "{}",
+                Section::new(Children::from(summary)).with_title("Update summary:")
+            );
+        }
 
-                println!("{changelog}");
-            }
+        if failed > 0 {
+            anyhow::bail!("{failed} of {} updates failed", outcomes.len());
         }
 
         Ok(())
@@ -97,67 +314,267 @@ impl super::Command for Args {
 
 impl Args {
     const FINISH_MESSAGE: &'static str = "✅";
+    const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+    /// Get how long ago the last update was, from the Scoop config
+    ///
+    /// Returns [`None`] if Scoop has never been updated, or the last update time can't be read,
+    /// so that an unreadable time never stops an update
+    fn since_last_update() -> Option<Duration> {
+        let config = match ScoopConfig::load() {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Failed to load the Scoop config to check the last update time: {e}");
+                return None;
+            }
+        };
+
+        let last_update = config.last_update.as_deref()?;
+
+        let Some(last_update) = parse_update_time(last_update) else {
+            warn!("Invalid last update time {last_update}, updating anyway");
+            return None;
+        };
+
+        // A last update time in the future is treated as just now
+        Some(
+            (Local::now().fixed_offset() - last_update)
+                .to_std()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// List the installed apps, and the buckets they were installed from
+    fn installed_apps(ctx: &impl ScoopContext) -> anyhow::Result<Vec<(String, String)>> {
+        Ok(install::Manifest::list_all_unchecked(ctx)?
+            .into_iter()
+            .filter_map(|app| {
+                let bucket = app.bucket.clone()?;
+
+                Some((unsafe { app.name() }.to_string(), bucket))
+            })
+            .collect())
+    }
+
+    fn scoop_path(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.apps_path().join("scoop").join("current")
+    }
+
+    fn preview(&self, ctx: &impl ScoopContext, buckets: &[Bucket]) -> anyhow::Result<()> {
+        let installed_apps = Self::installed_apps(ctx)?;
+        let installed_apps = dry_run::installed_by_bucket(&installed_apps);
+
+        let scoop_preview = if self.skip_scoop {
+            None
+        } else {
+            Some((
+                Cow::Borrowed(journal::SCOOP),
+                self.fetch_with_retries(journal::SCOOP, &Self::scoop_path(ctx), &[]),
+            ))
+        };
+
+        // Each bucket is fetched separately, so that one failing bucket doesn't hide the others' changes
+        let bucket_previews = self.thread_pool()?.install(|| {
+            buckets
+                .par_iter()
+                .map(|bucket| {
+                    let name = bucket.name();
+                    let installed = installed_apps
+                        .get(name.as_ref())
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+
+                    let result = self.fetch_with_retries(
+                        name.as_ref(),
+                        &ctx.buckets_path().join(name.as_ref()),
+                        installed,
+                    );
+
+                    (name, result)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let results = scoop_preview
+            .into_iter()
+            .chain(bucket_previews)
+            .collect_vec();
+
+        let previews = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok()?.as_ref())
+            .collect_vec();
+
+        let failed = results
+            .iter()
+            .filter_map(|(name, result)| Some((name.as_ref(), result.as_ref().err()?)))
+            .collect_vec();
+
+        if self.json {
+            let output = JsonPreview {
+                previews,
+                failed: failed
+                    .iter()
+                    .map(|(name, e)| (*name, format!("{e:#}")))
+                    .collect(),
+            };
+
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else if previews.is_empty() && failed.is_empty() {
+            println!("Everything is up to date");
+        } else {
+            if !previews.is_empty() {
+                let sections = previews
+                    .into_iter()
+                    .flat_map(dry_run::Preview::to_sections)
+                    .collect::<Sections<_>>();
+
+                println!("{sections}");
+            }
+
+            if !failed.is_empty() {
+                let failed = failed
+                    .iter()
+                    .map(|(name, e)| format!("{name} ❌ Failed: {e:#}"))
+                    .collect_vec();
+
+                print!(
+                    "{}",
+                    Section::new(Children::from(failed)).with_title("Failed to fetch updates:")
+                );
+            }
+        }
+
+        if !failed.is_empty() {
+            anyhow::bail!(
+                "{} of {} repositories could not be fetched",
+                failed.len(),
+                results.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the incoming changes for a repository, retrying network errors
+    fn fetch_with_retries(
+        &self,
+        name: &str,
+        path: &Path,
+        installed: &[&str],
+    ) -> anyhow::Result<Option<dry_run::Preview>> {
+        retry_with_backoff(self.retries, Self::INITIAL_RETRY_DELAY, || {
+            dry_run::Preview::fetch(name, path, installed)
+        })
+    }
+
+    /// Build a thread pool limited to `--jobs` threads
+    fn thread_pool(&self) -> anyhow::Result<ThreadPool> {
+        let mut builder = ThreadPoolBuilder::new();
+
+        if let Some(jobs) = self.jobs {
+            builder = builder.num_threads(jobs);
+        }
+
+        Ok(builder.build()?)
+    }
 
     fn update_scoop(
         &self,
         ctx: &impl ScoopContext,
         longest_bucket_name: usize,
         style: ProgressStyle,
-    ) -> anyhow::Result<Option<Vec<String>>> {
-        let repo = ctx.open_repo().context("missing user repository")??;
-
+    ) -> Outcome {
         let pb = ProgressBar::new(1)
             .with_style(style)
             .with_message("Checking for updates")
             .with_prefix(format!("🍨 {:<longest_bucket_name$}", "Scoop"))
             .with_finish(ProgressFinish::WithMessage(Self::FINISH_MESSAGE.into()));
 
-        let changelog = self.update(ctx, &repo, &pb)?;
+        let result = ctx
+            .open_repo()
+            .context("missing user repository")
+            .and_then(|repo| {
+                let repo = repo?;
+
+                self.update(ctx, &repo, &Self::scoop_path(ctx), journal::SCOOP, &pb)
+            });
 
-        Ok(changelog)
+        Self::finish(&pb, Outcome::from_result(result))
     }
 
     fn update_buckets<'a>(
         &self,
         ctx: &impl ScoopContext,
         outdated_buckets: &'a [(Bucket, ProgressBar)],
-    ) -> anyhow::Result<Vec<(Cow<'a, str>, Vec<String>)>> {
-        let bucket_changelogs = outdated_buckets
-            .par_iter()
-            .map(|(bucket, pb)| -> buckets::Result<_> {
-                let repo = bucket.open_repo()?;
-
-                let changelog = self.update(ctx, &repo, pb)?;
+    ) -> anyhow::Result<Vec<(Cow<'a, str>, Outcome)>> {
+        let bucket_outcomes = self.thread_pool()?.install(|| {
+            outdated_buckets
+                .par_iter()
+                .map(|(bucket, pb)| {
+                    let name = bucket.name();
+                    let path = ctx.buckets_path().join(name.as_ref());
+
+                    let result = bucket
+                        .open_repo()
+                        .map_err(anyhow::Error::from)
+                        .and_then(|repo| self.update(ctx, &repo, &path, &name, pb));
+
+                    (name, Self::finish(pb, Outcome::from_result(result)))
+                })
+                .collect()
+        });
+
+        Ok(bucket_outcomes)
+    }
 
-                Ok((bucket.name(), changelog.unwrap_or_default()))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+    fn finish(pb: &ProgressBar, outcome: Outcome) -> Outcome {
+        if let Outcome::Failed(e) = &outcome {
+            pb.abandon_with_message(format!("❌ {e:#}"));
+        }
 
-        Ok(bucket_changelogs)
+        outcome
     }
 
     fn update(
         &self,
         ctx: &impl ScoopContext,
         repo: &Repo,
+        path: &Path,
+        name: &str,
         pb: &ProgressBar,
-    ) -> git::Result<Option<Vec<String>>> {
-        if !repo.outdated()? {
+    ) -> anyhow::Result<Option<Pulled>> {
+        let previous_head = crate::git::head(path)?;
+
+        // Only the fetch is retried, as a pull that failed after moving `HEAD` would look up to date when retried,
+        // losing the previous head the changelog and journal are built from
+        let outdated = retry_with_backoff(self.retries, Self::INITIAL_RETRY_DELAY, || {
+            crate::git::Incoming::fetch(path)
+        })?
+        .is_some();
+
+        if !outdated {
             pb.finish_with_message("✅ No updates available");
             return Ok(None);
         }
 
-        let changelog = if self.changelog {
-            repo.pull_with_changelog(ctx, Some(&Self::gen_stats_callback(pb)))?
-        } else {
-            repo.pull(ctx, Some(&Self::gen_stats_callback(pb)))?;
+        repo.pull(ctx, Some(&Self::gen_stats_callback(pb)))?;
 
+        let changelog = if self.changelog || self.json {
+            crate::git::log(path, previous_head, crate::git::head(path)?)?
+                .into_iter()
+                .map(|commit| changelog::Entry::new(name, commit))
+                .collect()
+        } else {
             vec![]
         };
 
         pb.finish_with_message(Self::FINISH_MESSAGE);
 
-        Ok(Some(changelog))
+        Ok(Some(Pulled {
+            previous_head: previous_head.to_string(),
+            changelog,
+        }))
     }
 
     fn gen_stats_callback(
@@ -181,3 +598,23 @@ impl Args {
         }
     }
 }
+
+/// Parse the last update time, as written by Scoop (RFC 3339), or in chrono's display format
+fn parse_update_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time)
+        .or_else(|_| DateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%.f %:z"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_update_time() {
+        assert!(parse_update_time("2024-01-15T12:30:00.1234567+01:00").is_some());
+        assert!(parse_update_time("2024-01-15 12:30:00.123456789 +01:00").is_some());
+        assert!(parse_update_time("last tuesday").is_none());
+        assert!(parse_update_time("").is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Changelogs of the commits pulled by `sfsu update`
+
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use chrono::FixedOffset;
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    git::LoggedCommit,
+    output::sectioned::{Children, Section, Sections, Text},
+    wrappers::time::NicerTime,
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Which commits to include in the changelog
+pub enum Scope {
+    #[default]
+    /// Every pulled commit
+    All,
+    /// Only commits that change the manifests of installed apps
+    Installed,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// How to print the changelog
+pub enum Format {
+    #[default]
+    Text,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A pulled commit
+pub struct Entry {
+    bucket: String,
+    id: String,
+    summary: String,
+    author: String,
+    time: Option<NicerTime<FixedOffset>>,
+    apps: Vec<String>,
+}
+
+impl Entry {
+    pub fn new(bucket: impl Into<String>, commit: LoggedCommit) -> Self {
+        Self {
+            bucket: bucket.into(),
+            id: commit.id.to_string(),
+            summary: commit.summary,
+            author: commit.author.with_show_emails(false).to_string(),
+            time: commit.time.map(NicerTime::from),
+            apps: commit
+                .paths
+                .iter()
+                .filter_map(|path| manifest_name(path))
+                .unique()
+                .collect(),
+        }
+    }
+
+    /// Check if the commit changes the manifest of any of the given installed apps
+    ///
+    /// `installed` maps bucket names to the apps installed from them
+    pub fn touches_installed(&self, installed: &HashMap<&str, Vec<&str>>) -> bool {
+        installed
+            .get(self.bucket.as_str())
+            .is_some_and(|apps| self.apps.iter().any(|app| apps.contains(&app.as_str())))
+    }
+
+    fn short_id(&self) -> &str {
+        &self.id[..7]
+    }
+}
+
+/// Print the changelog in the given format, grouped by bucket
+pub fn print(entries: &[&Entry], format: Format) {
+    let buckets = entries.iter().chunk_by(|entry| entry.bucket.as_str());
+
+    match format {
+        Format::Text => {
+            let sections = buckets
+                .into_iter()
+                .map(|(bucket, entries)| {
+                    let commits = entries
+                        .map(|entry| Text::new(format!("{} {}", entry.short_id(), entry.summary)))
+                        .collect_vec();
+
+                    Section::new(Children::from(commits)).with_title(format!("{bucket} changes:"))
+                })
+                .collect::<Sections<_>>();
+
+            println!("{sections}");
+        }
+        Format::Markdown => {
+            let mut output = String::new();
+
+            for (bucket, entries) in &buckets {
+                _ = writeln!(output, "### {bucket}\n");
+
+                for entry in entries {
+                    _ = write!(
+                        output,
+                        "- `{}` {} ({}",
+                        entry.short_id(),
+                        entry.summary,
+                        entry.author
+                    );
+
+                    if let Some(time) = &entry.time {
+                        _ = write!(output, ", {time}");
+                    }
+
+                    _ = writeln!(output, ")");
+                }
+
+                _ = writeln!(output);
+            }
+
+            print!("{output}");
+        }
+    }
+}
+
+/// Get the app name from a manifest path, either `bucket/<name>.json` or `<name>.json` in the bucket root
+pub fn manifest_name(path: &Path) -> Option<String> {
+    if path.extension()? != "json" {
+        return None;
+    }
+
+    let parent = path.parent()?;
+    if parent != Path::new("bucket") && parent != Path::new("") {
+        return None;
+    }
+
+    Some(path.file_stem()?.to_string_lossy().to_string())
+}
@@ -0,0 +1,117 @@
+//! Previewing incoming Scoop and bucket changes without merging them
+
+use std::{collections::HashMap, path::Path};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    git::Incoming,
+    output::sectioned::{Children, Section, Text},
+    wrappers::version::Change,
+};
+
+use super::changelog::manifest_name;
+
+#[derive(Debug, Serialize)]
+/// Changes that would be applied to a repository by `sfsu update`
+pub struct Preview {
+    name: String,
+    commits: Vec<String>,
+    apps: Vec<String>,
+}
+
+impl Preview {
+    /// Fetch the repository at `path`, and preview the changes that would be merged
+    ///
+    /// `installed` is the names of the installed apps from this repository,
+    /// which are checked for version changes.
+    ///
+    /// Returns [`None`] if the repository is up to date
+    ///
+    /// # Errors
+    /// - The repository could not be fetched or read
+    pub fn fetch(
+        name: impl Into<String>,
+        path: &Path,
+        installed: &[&str],
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(incoming) = Incoming::fetch(path)? else {
+            return Ok(None);
+        };
+
+        let commits = incoming
+            .commits()?
+            .into_iter()
+            .map(|commit| {
+                let id = commit.id.to_string();
+
+                format!("{} {}", &id[..7], commit.summary)
+            })
+            .collect_vec();
+
+        let apps = incoming
+            .changed_paths()?
+            .into_iter()
+            .filter_map(|path| {
+                let app = manifest_name(&path)?;
+
+                if !installed.contains(&app.as_str()) {
+                    return None;
+                }
+
+                let current = manifest_version(incoming.read_current(&path)?)?;
+                let Some(available) = incoming.read_incoming(&path).and_then(manifest_version)
+                else {
+                    return Some(format!("{app}: {current} -> removed"));
+                };
+
+                let change = Change::between(&current, &available)?;
+
+                Some(format!("{app}: {current} -> {available} ({change})"))
+            })
+            .sorted()
+            .collect_vec();
+
+        Ok(Some(Self {
+            name: name.into(),
+            commits,
+            apps,
+        }))
+    }
+
+    pub fn to_sections(&self) -> Vec<Section<Text<String>>> {
+        let mut sections = vec![Section::new(Children::from(
+            self.commits.iter().cloned().map(Text::new).collect_vec(),
+        ))
+        .with_title(format!(
+            "{} ({} incoming commits):",
+            self.name,
+            self.commits.len()
+        ))];
+
+        if !self.apps.is_empty() {
+            sections.push(
+                Section::new(Children::from(
+                    self.apps.iter().cloned().map(Text::new).collect_vec(),
+                ))
+                .with_title(format!("{} installed apps that would change:", self.name)),
+            );
+        }
+
+        sections
+    }
+}
+
+/// Group installed apps by the bucket they were installed from
+pub fn installed_by_bucket(apps: &[(String, String)]) -> HashMap<&str, Vec<&str>> {
+    apps.iter()
+        .map(|(name, bucket)| (bucket.as_str(), name.as_str()))
+        .into_group_map()
+}
+
+fn manifest_version(contents: Vec<u8>) -> Option<String> {
+    let manifest: serde_json::Value = serde_json::from_slice(&contents).ok()?;
+
+    manifest.get("version")?.as_str().map(ToString::to_string)
+}
@@ -18,6 +18,7 @@ use crate::{
     errors::{RecoverableError, RecoverableResult},
     limits::RateLimiter,
     output::colours::{eprintln_green, eprintln_red, eprintln_yellow},
+    pattern::{Pattern, Syntax, SyntaxArgs},
 };
 
 impl RecoverableError for vt3::error::VtError {
@@ -92,9 +93,14 @@ fn extract_info(value: &serde_json::Value) -> anyhow::Result<(u64, u64)> {
 #[derive(Debug, Clone, Parser)]
 /// Scan a file with `VirusTotal`
 pub struct Args {
-    // TODO: Use manifest reference and -a flag for scanning installed apps
-    #[clap(help = "The apps to scan for viruses")]
-    apps: Vec<package::Reference>,
+    #[clap(
+        value_name = "APPS",
+        help = "The apps to scan for viruses. Treated as patterns matching installed apps if --glob, --regex or --literal is passed"
+    )]
+    apps: Vec<String>,
+
+    #[clap(flatten)]
+    syntax: SyntaxArgs,
 
     #[clap(
         short,
@@ -137,17 +143,45 @@ impl super::Command for Args {
         let client = vt3::VtClient::new(&api_key).user_agent(USER_AGENT);
 
         #[allow(clippy::redundant_closure)]
-        let manifests = if self.all {
+        let manifests = if self.all || self.syntax.is_explicit() {
+            let patterns = if self.all {
+                vec![]
+            } else {
+                self.apps
+                    .iter()
+                    .map(|pattern| {
+                        Pattern::whole(
+                            pattern,
+                            self.syntax.syntax_or(Syntax::Glob),
+                            self.case_sensitive,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+
             ctx.installed_apps()?
                 .into_par_iter()
+                .filter(|path| {
+                    patterns.is_empty()
+                        || path.file_name().is_some_and(|name| {
+                            let name = name.to_string_lossy();
+                            patterns.iter().any(|pattern| pattern.is_match(&name))
+                        })
+                })
                 .map(|path| path.join("current").join("manifest.json"))
                 .filter(|path| path.exists())
                 // The closure is redundant, but it's necessary to avoid a rust-analyzer error
                 .map(|path| Manifest::from_path(path))
                 .collect::<Result<_, _>>()?
         } else {
-            let manifests = self
+            // Without a pattern syntax, the apps are references (i.e `bucket/app`), rather than patterns
+            let references = self
                 .apps
+                .iter()
+                .map(|app| app.parse::<package::Reference>())
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let manifests = references
                 .iter()
                 .map(|reference| async move { reference.list_manifests(ctx).await });
 
@@ -0,0 +1,298 @@
+//! Shell completions, generated from the clap command tree
+//!
+//! The static parts (subcommands and flags) are embedded in the generated script.
+//! Package, bucket and cache names are completed at runtime by calling the hidden `sfsu complete` command.
+
+use std::{collections::BTreeSet, path::Path};
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use sprinkles::contexts::ScoopContext;
+
+use crate::shell::Shell;
+
+mod bash;
+mod elvish;
+mod fish;
+mod nu;
+mod powershell;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The kinds of values that are completed at runtime
+pub enum Kind {
+    /// Installed apps
+    Installed,
+    /// Manifests from all buckets
+    Manifest,
+    /// Added buckets
+    Bucket,
+    /// Apps with entries in the download cache
+    Cache,
+}
+
+impl Kind {
+    /// Get the kind of values an argument takes from its value name
+    ///
+    /// Positionals that should be completed set one of these value names explicitly,
+    /// so that renaming a field doesn't change how it is completed
+    fn from_arg(arg: &clap::Arg) -> Option<Self> {
+        let value_name = arg.get_value_names()?.first()?.as_str();
+
+        match value_name {
+            "APP" | "APPS" => Some(Self::Installed),
+            "PACKAGE" | "PACKAGES" => Some(Self::Manifest),
+            "BUCKET" | "BUCKETS" => Some(Self::Bucket),
+            "CACHED_APP" | "CACHED_APPS" => Some(Self::Cache),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// The name of the kind, as passed to `sfsu complete`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Installed => "installed",
+            Self::Manifest => "manifest",
+            Self::Bucket => "bucket",
+            Self::Cache => "cache",
+        }
+    }
+
+    /// List the values of this kind starting with `prefix`, sorted and deduplicated
+    ///
+    /// # Errors
+    /// - The values could not be listed
+    pub fn candidates(self, ctx: &impl ScoopContext, prefix: &str) -> anyhow::Result<Vec<String>> {
+        let candidates: BTreeSet<String> = match self {
+            Self::Installed => ctx
+                .installed_apps()?
+                .iter()
+                .filter_map(|path| file_name(path))
+                .collect(),
+            Self::Bucket => read_dir_names(&ctx.buckets_path())?.collect(),
+            Self::Cache => read_dir_names(&ctx.cache_path())?
+                .filter_map(|name| Some(name.split_once('#')?.0.to_string()))
+                .collect(),
+            Self::Manifest => {
+                // `bucket/app` references only complete manifests from that bucket
+                if let Some((bucket, _)) = prefix.split_once('/') {
+                    bucket_manifests(ctx, bucket)
+                        .map(|name| format!("{bucket}/{name}"))
+                        .collect()
+                } else {
+                    read_dir_names(&ctx.buckets_path())?
+                        .flat_map(|bucket| bucket_manifests(ctx, &bucket).collect_vec())
+                        .collect()
+                }
+            }
+        };
+
+        Ok(candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect())
+    }
+}
+
+/// A command in the command tree
+pub struct Node {
+    /// The subcommand names leading to this command, separated by spaces. Empty for the root command
+    path: String,
+    subcommands: Vec<String>,
+    flags: Vec<Flag>,
+    /// The kind of values the command's positional arguments take
+    kind: Option<Kind>,
+}
+
+/// A flag a command accepts
+pub struct Flag {
+    long: Option<String>,
+    short: Option<char>,
+    takes_value: bool,
+    help: String,
+}
+
+impl Flag {
+    /// Every spelling of the flag, i.e `-v` and `--verbose`
+    fn spellings(&self) -> impl Iterator<Item = String> + '_ {
+        self.long
+            .iter()
+            .map(|long| format!("--{long}"))
+            .chain(self.short.iter().map(|short| format!("-{short}")))
+    }
+}
+
+/// Walk the command tree, skipping hidden commands and arguments
+#[must_use]
+pub fn tree(mut command: clap::Command) -> Vec<Node> {
+    // Building the command propagates global arguments to subcommands
+    command.build();
+
+    let mut nodes = vec![];
+    walk(&command, "", &mut nodes);
+
+    nodes
+}
+
+fn walk(command: &clap::Command, path: &str, nodes: &mut Vec<Node>) {
+    let subcommands = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .collect_vec();
+
+    let flags = command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .map(|arg| Flag {
+            long: arg.get_long().map(ToString::to_string),
+            short: arg.get_short(),
+            takes_value: arg.get_action().takes_values(),
+            help: arg.get_help().map(ToString::to_string).unwrap_or_default(),
+        })
+        .collect();
+
+    let kind = command.get_positionals().find_map(Kind::from_arg);
+
+    nodes.push(Node {
+        path: path.to_string(),
+        subcommands: subcommands
+            .iter()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect(),
+        flags,
+        kind,
+    });
+
+    // Clap's generated `help` subcommand mirrors the whole tree, so it isn't worth completing further
+    for subcommand in subcommands
+        .into_iter()
+        .filter(|subcommand| subcommand.get_name() != "help")
+    {
+        let path = if path.is_empty() {
+            subcommand.get_name().to_string()
+        } else {
+            format!("{path} {}", subcommand.get_name())
+        };
+
+        walk(subcommand, &path, nodes);
+    }
+}
+
+#[must_use]
+/// Generate the completion script for the given shell
+pub fn generate(shell: Shell, command: clap::Command) -> String {
+    let name = command.get_name().to_string();
+    let nodes = tree(command);
+
+    match shell {
+        Shell::Powershell => powershell::generate(&name, &nodes),
+        Shell::Bash => bash::generate(&name, &nodes, shell),
+        // Zsh can use bash completions, which saves maintaining a separate script
+        Shell::Zsh => format!(
+            "autoload -U +X bashcompinit && bashcompinit\n\n{}",
+            bash::generate(&name, &nodes, shell)
+        ),
+        Shell::Nu => nu::generate(&name, &nodes),
+        Shell::Fish => fish::generate(&name, &nodes),
+        Shell::Elvish => elvish::generate(&name, &nodes),
+    }
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
+fn read_dir_names(path: &Path) -> std::io::Result<impl Iterator<Item = String>> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().to_string()))
+}
+
+/// List the manifest names in a bucket, without reading the manifests
+fn bucket_manifests(ctx: &impl ScoopContext, bucket: &str) -> impl Iterator<Item = String> {
+    let bucket_path = ctx.buckets_path().join(bucket);
+    let manifests_path = bucket_path.join("bucket");
+
+    // Some buckets keep manifests in the root, rather than the `bucket` folder
+    let path = if manifests_path.is_dir() {
+        manifests_path
+    } else {
+        bucket_path
+    };
+
+    read_dir_names(&path)
+        .into_iter()
+        .flatten()
+        .filter_map(|name| Some(name.strip_suffix(".json")?.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    /// The value names of positionals that take free text, rather than names that can be completed
+    const NOT_COMPLETED: &[&str] = &["PATTERN", "NAME", "REPO"];
+
+    /// List the value name and completed kind of every visible positional, with the path of its command
+    fn positionals(
+        command: &clap::Command,
+        path: &str,
+        found: &mut Vec<(String, String, Option<Kind>)>,
+    ) {
+        for arg in command.get_positionals().filter(|arg| !arg.is_hide_set()) {
+            let value_name = arg
+                .get_value_names()
+                .and_then(|names| names.first())
+                .map(ToString::to_string)
+                .unwrap_or_default();
+
+            found.push((path.to_string(), value_name, Kind::from_arg(arg)));
+        }
+
+        for subcommand in command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+        {
+            positionals(
+                subcommand,
+                &format!("{path} {}", subcommand.get_name()),
+                found,
+            );
+        }
+    }
+
+    #[test]
+    fn test_positionals_have_known_kinds() {
+        let mut command = crate::Args::command();
+        command.build();
+
+        let mut found = vec![];
+        positionals(&command, "sfsu", &mut found);
+
+        for (path, value_name, kind) in found {
+            assert!(
+                kind.is_some() || NOT_COMPLETED.contains(&value_name.as_str()),
+                "`{path}` has a positional with value name \"{value_name}\", which isn't completed. \
+                Set its value name to one of the completed kinds, or add it to `NOT_COMPLETED`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_completed_kinds() {
+        let nodes = tree(crate::Args::command());
+        let kind = |path: &str| {
+            nodes
+                .iter()
+                .find(|node| node.path == path)
+                .and_then(|node| node.kind)
+        };
+
+        assert_eq!(kind("app info"), Some(Kind::Manifest));
+        assert_eq!(kind("app purge"), Some(Kind::Installed));
+        assert_eq!(kind("bucket remove"), Some(Kind::Bucket));
+        assert_eq!(kind("update"), Some(Kind::Bucket));
+    }
+}
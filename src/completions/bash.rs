@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use crate::shell::Shell;
+
+use super::Node;
+
+pub fn generate(name: &str, nodes: &[Node], shell: Shell) -> String {
+    let shell_config = shell.config();
+    let mut cases = String::new();
+
+    for node in nodes {
+        _ = writeln!(
+            cases,
+            "        '{path}') subcommands='{subcommands}'; flags='{flags}'; kind='{kind}' ;;",
+            path = node.path,
+            subcommands = node.subcommands.join(" "),
+            flags = node.flags.iter().flat_map(super::Flag::spellings).join(" "),
+            kind = node.kind.map(super::Kind::name).unwrap_or_default(),
+        );
+    }
+
+    format!(
+        r#"_{name}_node() {{
+    case "$1" in
+{cases}        *) subcommands=''; flags=''; kind='' ;;
+    esac
+}}
+
+_{name}() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" path='' word subcommands flags kind i
+    _{name}_node ''
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        word="${{COMP_WORDS[i]}}"
+        if [[ " $subcommands " == *" $word "* ]]; then
+            path="${{path:+$path }}$word"
+            _{name}_node "$path"
+        fi
+    done
+
+    if [[ $cur == -* ]]; then
+        COMPREPLY=($(compgen -W "$flags" -- "$cur"))
+    elif [[ -n $subcommands ]]; then
+        COMPREPLY=($(compgen -W "$subcommands" -- "$cur"))
+    elif [[ -n $kind ]]; then
+        COMPREPLY=($({name}.exe complete "$kind" "$cur" 2>/dev/null))
+    fi
+}}
+
+complete -F _{name} {name} {name}.exe
+
+# Add the following to the end of your ~/.{shell_config}
+#   source <({name}.exe completions --shell {shell})
+"#
+    )
+}
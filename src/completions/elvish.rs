@@ -0,0 +1,67 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use super::Node;
+
+fn list<'a>(items: impl IntoIterator<Item = &'a str>) -> String {
+    format!(
+        "[{}]",
+        items.into_iter().map(|item| format!("'{item}'")).join(" ")
+    )
+}
+
+pub fn generate(name: &str, nodes: &[Node]) -> String {
+    let mut entries = String::new();
+
+    for node in nodes {
+        let flags = node
+            .flags
+            .iter()
+            .flat_map(super::Flag::spellings)
+            .collect_vec();
+
+        _ = writeln!(
+            entries,
+            "    &'{path}'=[&subcommands={subcommands} &flags={flags} &kind='{kind}']",
+            path = node.path,
+            subcommands = list(node.subcommands.iter().map(String::as_str)),
+            flags = list(flags.iter().map(String::as_str)),
+            kind = node.kind.map(super::Kind::name).unwrap_or_default(),
+        );
+    }
+
+    format!(
+        r"use str
+
+var {name}-nodes = [
+{entries}]
+
+set edit:completion:arg-completer[{name}] = {{|@words|
+    var path = ''
+    var node = ${name}-nodes['']
+
+    for word $words[1..-1] {{
+        var next = (if (eq $path '') {{ put $word }} else {{ put $path' '$word }})
+        if (and (has-value $node[subcommands] $word) (has-key ${name}-nodes $next)) {{
+            set path = $next
+            set node = ${name}-nodes[$path]
+        }}
+    }}
+
+    var cur = $words[-1]
+    if (str:has-prefix $cur '-') {{
+        all $node[flags]
+    }} elif (> (count $node[subcommands]) 0) {{
+        all $node[subcommands]
+    }} elif (not-eq $node[kind] '') {{
+        {name}.exe complete $node[kind] $cur
+    }}
+}}
+set edit:completion:arg-completer[{name}.exe] = $edit:completion:arg-completer[{name}]
+
+# Add the following to the end of your ~/.config/elvish/rc.elv
+#   eval ({name}.exe completions --shell elvish | slurp)
+"
+    )
+}
@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use super::Node;
+
+pub fn generate(name: &str, nodes: &[Node]) -> String {
+    let mut cases = String::new();
+
+    for node in nodes {
+        _ = writeln!(
+            cases,
+            "        case '{path}'\n            \
+            echo '{subcommands}'\n            \
+            echo '{flags}'\n            \
+            echo '{kind}'",
+            path = node.path,
+            subcommands = node.subcommands.join(" "),
+            flags = node.flags.iter().flat_map(super::Flag::spellings).join(" "),
+            kind = node.kind.map(super::Kind::name).unwrap_or_default(),
+        );
+    }
+
+    format!(
+        r#"# Prints the subcommands, flags and value kind of a command, one per line
+function __{name}_node
+    switch "$argv[1]"
+{cases}        case '*'
+            printf '\n\n\n'
+    end
+end
+
+function __{name}_complete
+    set -l tokens (commandline -opc)
+    set -l cur (commandline -ct)
+    set -l path ''
+    set -l node (__{name}_node '')
+
+    for token in $tokens[2..-1]
+        if contains -- $token (string split ' ' -- $node[1])
+            set path (string trim -- "$path $token")
+            set node (__{name}_node $path)
+        end
+    end
+
+    if string match -q -- '-*' $cur
+        string split ' ' -- $node[2]
+    else if test -n "$node[1]"
+        string split ' ' -- $node[1]
+    else if test -n "$node[3]"
+        {name}.exe complete $node[3] $cur 2>/dev/null
+    end
+end
+
+complete -c {name} -f -a '(__{name}_complete)'
+complete -c {name}.exe -f -a '(__{name}_complete)'
+
+# Add the following to the end of your ~/.config/fish/config.fish
+#   {name}.exe completions --shell fish | source
+"#
+    )
+}
@@ -0,0 +1,65 @@
+use std::fmt::Write;
+
+use super::{Kind, Node};
+
+pub fn generate(name: &str, nodes: &[Node]) -> String {
+    let mut output = String::new();
+
+    for kind in [Kind::Installed, Kind::Manifest, Kind::Bucket, Kind::Cache] {
+        let kind = kind.name();
+
+        _ = writeln!(
+            output,
+            "def \"nu-complete {name} {kind}\" [] {{ ^{name} complete {kind} | lines }}"
+        );
+    }
+
+    for node in nodes {
+        let command = if node.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name} {}", node.path)
+        };
+
+        _ = writeln!(output, "\nexport extern \"{command}\" [");
+
+        for flag in &node.flags {
+            let spelling = match (&flag.long, flag.short) {
+                (Some(long), Some(short)) => format!("--{long}(-{short})"),
+                (Some(long), None) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => continue,
+            };
+            let value_type = if flag.takes_value { ": string" } else { "" };
+
+            match flag.help.lines().next() {
+                Some(help) if !help.is_empty() => {
+                    _ = writeln!(output, "    {spelling}{value_type} # {help}");
+                }
+                _ => _ = writeln!(output, "    {spelling}{value_type}"),
+            }
+        }
+
+        match node.kind {
+            Some(kind) => {
+                _ = writeln!(
+                    output,
+                    "    ...rest: string@\"nu-complete {name} {kind}\"",
+                    kind = kind.name()
+                );
+            }
+            None => _ = writeln!(output, "    ...rest: string"),
+        }
+
+        _ = writeln!(output, "]");
+    }
+
+    _ = write!(
+        output,
+        "\n# To add this to your config, run `{name} completions --shell nu | save ~/.cache/{name}-completions.nu`\n\
+        # And then in your config.nu add the following line to the end:\n\
+        #   source ~/.cache/{name}-completions.nu\n"
+    );
+
+    output
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use super::Node;
+
+fn array<'a>(items: impl IntoIterator<Item = &'a str>) -> String {
+    format!(
+        "@({})",
+        items.into_iter().map(|item| format!("'{item}'")).join(", ")
+    )
+}
+
+pub fn generate(name: &str, nodes: &[Node]) -> String {
+    let mut entries = String::new();
+
+    for node in nodes {
+        let flags = node
+            .flags
+            .iter()
+            .flat_map(super::Flag::spellings)
+            .collect_vec();
+
+        _ = writeln!(
+            entries,
+            "        '{path}' = @{{ Subcommands = {subcommands}; Flags = {flags}; Kind = '{kind}' }}",
+            path = node.path,
+            subcommands = array(node.subcommands.iter().map(String::as_str)),
+            flags = array(flags.iter().map(String::as_str)),
+            kind = node.kind.map(super::Kind::name).unwrap_or_default(),
+        );
+    }
+
+    format!(
+        r#"Register-ArgumentCompleter -Native -CommandName '{name}', '{name}.exe' -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $nodes = @{{
+{entries}    }}
+
+    $path = ''
+    $node = $nodes['']
+
+    # Walk the words before the one being completed
+    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {{
+        if ($element.Extent.EndOffset -ge $cursorPosition) {{ break }}
+
+        $word = $element.ToString()
+        if ($node.Subcommands -contains $word) {{
+            $path = "$path $word".Trim()
+            $node = $nodes[$path]
+        }}
+    }}
+
+    $candidates = if ($wordToComplete.StartsWith('-')) {{
+        $node.Flags
+    }} elseif ($node.Subcommands.Count -gt 0) {{
+        $node.Subcommands
+    }} elseif ($node.Kind) {{
+        & {name}.exe complete $node.Kind $wordToComplete
+    }} else {{
+        @()
+    }}
+
+    $candidates | Where-Object {{ $_.StartsWith($wordToComplete) }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+
+# To add this to your config, add the following line to the end of your PowerShell profile:
+#     Invoke-Expression (&{name} completions | Out-String)
+"#
+    )
+}
@@ -0,0 +1,66 @@
+//! sfsu's own configuration, stored alongside Scoop's
+//!
+//! Unlike Scoop's config, this is only read by sfsu, so it can hold settings Scoop doesn't know about.
+
+use std::{collections::BTreeMap, fs::File, io::BufReader, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sprinkles::contexts::ScoopContext;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+/// sfsu's configuration
+pub struct Config {
+    /// Extra hooks, mapping `scoop <name>` to the sfsu command and arguments to run instead
+    ///
+    /// For example, `"up": "update --changelog"` runs `sfsu update --changelog` for `scoop up`
+    pub hooks: BTreeMap<String, String>,
+    /// The sizes, in MiB, above which `sfsu checkup` warns about disk usage
+    pub disk_usage: DiskUsage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+/// The sizes, in MiB, above which `sfsu checkup` warns about disk usage
+pub struct DiskUsage {
+    /// The download cache
+    pub cache: u64,
+    /// sfsu's logs
+    pub logs: u64,
+    /// Every installed app's old versions, combined
+    pub old_versions: u64,
+}
+
+impl Default for DiskUsage {
+    fn default() -> Self {
+        Self {
+            cache: 2048,
+            logs: 100,
+            old_versions: 2048,
+        }
+    }
+}
+
+impl Config {
+    /// The path to the config, in the Scoop directory
+    pub fn path(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.path().join("sfsu").join("config.json")
+    }
+
+    /// Load the config, or the default config if it doesn't exist
+    ///
+    /// # Errors
+    /// - The config exists but could not be read or parsed
+    pub fn load(ctx: &impl ScoopContext) -> anyhow::Result<Self> {
+        let path = Self::path(ctx);
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file = File::open(&path)?;
+
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))
+    }
+}
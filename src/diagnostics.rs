@@ -1,11 +1,26 @@
 //! Scoop diagnostics helpers
+//!
+//! Each diagnostic is a [`Check`], and the checks `sfsu checkup` runs are listed in a [`Registry`].
+//! Checks access the system through a [`Provider`], so they can be tested against a fake system.
 
-use std::{ffi::OsString, os::windows::ffi::OsStringExt};
+mod buckets;
+mod disk;
+mod integrity;
+pub mod provider;
+mod shadowing;
+mod system;
 
-use anyhow::Error;
-use itertools::Itertools;
+#[cfg(test)]
+mod fake;
+
+use std::path::PathBuf;
+
+use rayon::prelude::*;
 use serde::Serialize;
-use sprinkles::{buckets::Bucket, contexts::ScoopContext};
+
+use crate::config::Config;
+
+pub use provider::{Provider, System};
 
 #[derive(Debug, Copy, Clone, Serialize)]
 /// The status of long paths
@@ -18,222 +33,395 @@ pub enum LongPathsStatus {
     Disabled,
 }
 
-#[derive(Debug, Copy, Clone, Serialize)]
-/// A helper program
-pub struct Helper {
-    /// The executable name
-    pub exe: &'static str,
-    /// The name of the program
-    pub name: &'static str,
-    /// The reason the program is needed
-    pub reason: &'static str,
-    /// The packages that provide the program
-    pub packages: &'static [&'static str],
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// How serious the problems a check finds are
+pub enum Severity {
+    /// Worth knowing about, but not a problem
+    Info,
+    /// May cause problems
+    Warning,
+    /// Something is broken
+    Error,
 }
 
-const EXPECTED_HELPERS: &[Helper] = &[
-    Helper {
-        exe: "7z",
-        name: "7-Zip",
-        reason: "unpacking most programs",
-        packages: &["7zip"],
-    },
-    Helper {
-        exe: "innounp",
-        name: "Inno Setup Unpacker",
-        reason: "unpacking InnoSetup files",
-        packages: &["innounp"],
-    },
-    Helper {
-        exe: "dark",
-        name: "Dark",
-        reason: "unpacking installers created with the WiX toolkit",
-        packages: &["dark", "wixtoolset"],
-    },
-];
-
-#[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Clone, Serialize)]
-/// Diagnostics information
-pub struct Diagnostics {
-    /// If git is installed
-    pub git_installed: bool,
-    /// The status of long paths
-    pub long_paths: LongPathsStatus,
-    /// If the main bucket exists
-    pub main_bucket: bool,
-    /// If the user has developer mode enabled
-    pub windows_developer: bool,
-    /// If Windows Defender is ignoring the Scoop directory
-    pub windows_defender: bool,
-    /// The missing helper programs
-    pub missing_helpers: Vec<Helper>,
-    /// If the Scoop directory is on an NTFS filesystem
-    pub scoop_ntfs: bool,
+impl Severity {
+    #[must_use]
+    /// The emoji shown next to findings of this severity
+    pub const fn emoji(self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ️",
+            Severity::Warning => "⚠️",
+            Severity::Error => "❌",
+        }
+    }
 }
 
-impl Diagnostics {
-    /// Collect all diagnostics
-    ///
-    /// # Errors
-    /// - Unable to check long paths
-    /// - Unable to check main bucket
-    /// - Unable to check windows developer status
-    /// - Unable to check windows defender status
-    pub fn collect(ctx: &impl ScoopContext) -> Result<Self, Error> {
-        let git_installed = Self::git_installed();
-        debug!("Check git is installed");
-        let main_bucket = Self::check_main_bucket(ctx)?;
-        debug!("Checked main bucket");
-        let long_paths = Self::check_long_paths()?;
-        debug!("Checked long paths");
-        let windows_developer = Self::get_windows_developer_status()?;
-        debug!("Checked developer mode");
-
-        let windows_defender = if quork::root::is_root()? {
-            Self::check_windows_defender(ctx)?
-        } else {
-            false
-        };
-        debug!("Checked windows defender");
+#[derive(Debug, Clone, Serialize)]
+/// A problem found by a check
+pub struct Finding {
+    /// A description of the problem
+    pub message: String,
+    /// How to fix the problem
+    pub remedy: Option<String>,
+    #[serde(skip)]
+    /// The path the problem is with, used by automatic fixes
+    pub path: Option<PathBuf>,
+}
 
-        let missing_helpers = EXPECTED_HELPERS
-            .iter()
-            .filter(|helper| which::which(helper.exe).is_err())
-            .copied()
-            .collect();
+impl Finding {
+    #[must_use]
+    /// Create a new finding
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            remedy: None,
+            path: None,
+        }
+    }
 
-        let scoop_ntfs = Self::is_ntfs(ctx)?;
+    #[must_use]
+    /// Suggest how to fix the problem
+    pub fn with_remedy(mut self, remedy: impl Into<String>) -> Self {
+        self.remedy = Some(remedy.into());
+        self
+    }
 
-        Ok(Self {
-            git_installed,
-            long_paths,
-            main_bucket,
-            windows_developer,
-            windows_defender,
-            missing_helpers,
-            scoop_ntfs,
-        })
+    #[must_use]
+    /// Set the path the problem is with
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
     }
+}
 
-    #[allow(unreachable_code)]
-    /// Check if Windows Defender is ignoring the Scoop directory
-    ///
-    /// # Errors
-    /// - Unable to read the registry
-    /// - Unable to open the registry key
-    /// - Unable to check if the key exists
-    pub fn check_windows_defender(ctx: &impl ScoopContext) -> windows::core::Result<bool> {
-        use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+/// The status of an entry in a checkup report
+pub enum Status {
+    /// The check passed
+    Pass,
+    /// The check found a problem that may cause issues
+    Warn,
+    /// The check found something broken
+    Fail,
+    /// The check was not run, or could not be run
+    Skipped,
+}
 
-        let scoop_dir = ctx.path();
-        let key = RegKey::predef(HKEY_LOCAL_MACHINE)
-            .open_subkey(r"SOFTWARE\Microsoft\Windows Defender\Exclusions\Paths")?;
+impl From<Severity> for Status {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            // Informational findings are worth reporting, but don't fail the check
+            Severity::Info => Status::Pass,
+            Severity::Warning => Status::Warn,
+            Severity::Error => Status::Fail,
+        }
+    }
+}
 
-        Ok(key.open_subkey(scoop_dir).is_ok())
+#[derive(Debug, Clone, Serialize)]
+/// An entry in a checkup report
+///
+/// This is the format of `sfsu checkup --json`, so fields must not be removed or renamed
+pub struct ReportEntry {
+    /// The id of the check
+    pub id: &'static str,
+    /// The status of the check
+    pub status: Status,
+    /// A description of the problem, or the check's title if it passed
+    pub message: String,
+    /// How to fix the problem
+    pub remedy: Option<String>,
+}
+
+impl ReportEntry {
+    #[must_use]
+    /// Create an entry for a check that was not run
+    pub fn skipped(id: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: Status::Skipped,
+            message: message.into(),
+            remedy: None,
+        }
     }
+}
+
+/// A diagnostic check
+pub trait Check: Send + Sync {
+    /// The id of the check, used to select checks with `--only` and `--skip`
+    fn id(&self) -> &'static str;
+
+    /// What the check looks for, phrased as the check passing
+    fn title(&self) -> &'static str;
 
-    /// Check if the main bucket exists
+    /// How serious the problems the check finds are
+    fn severity(&self) -> Severity;
+
+    /// Run the check, returning any problems found
     ///
     /// # Errors
-    /// - Unable to list buckets
-    pub fn check_main_bucket(ctx: &impl ScoopContext) -> Result<bool, Error> {
-        let buckets = Bucket::list_all(ctx)?;
+    /// - The check could not be run
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>>;
 
-        Ok(buckets.into_iter().any(|bucket| bucket.name() == "main"))
+    /// Whether problems found by the check can be fixed automatically with [`Check::fix`]
+    fn fixable(&self) -> bool {
+        false
     }
 
-    /// Check if long paths are enabled
+    /// Fix a problem found by the check
     ///
     /// # Errors
-    /// - Unable to read the registry
-    /// - Unable to read the OS version
-    pub fn check_long_paths() -> windows::core::Result<LongPathsStatus> {
-        use windows_version::OsVersion;
-        use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
-
-        let version = OsVersion::current();
+    /// - The check has no automatic fix
+    /// - The fix failed
+    fn fix(&self, _provider: &dyn Provider, _finding: &Finding) -> anyhow::Result<()> {
+        anyhow::bail!("{} has no automatic fix", self.id())
+    }
+}
 
-        let major_version = version.major;
-        debug!("Windows Major Version: {major_version}");
+/// The result of running a check
+pub struct Outcome<'a> {
+    /// The check that was run
+    pub check: &'a dyn Check,
+    /// The problems found, or the error running the check
+    pub findings: anyhow::Result<Vec<Finding>>,
+}
 
-        if major_version < 10 {
-            return Ok(LongPathsStatus::OldWindows);
+impl Outcome<'_> {
+    #[must_use]
+    /// Convert the outcome to report entries
+    ///
+    /// There is one entry per finding, or a single entry if the check passed or could not be run
+    pub fn report(&self) -> Vec<ReportEntry> {
+        let check = self.check;
+
+        match &self.findings {
+            Ok(findings) if findings.is_empty() => vec![ReportEntry {
+                id: check.id(),
+                status: Status::Pass,
+                message: check.title().to_string(),
+                remedy: None,
+            }],
+            Ok(findings) => findings
+                .iter()
+                .map(|finding| ReportEntry {
+                    id: check.id(),
+                    status: check.severity().into(),
+                    message: finding.message.clone(),
+                    remedy: finding.remedy.clone(),
+                })
+                .collect(),
+            Err(e) => vec![ReportEntry::skipped(
+                check.id(),
+                format!("{} could not be checked: {e}", check.title()),
+            )],
         }
+    }
+}
 
-        let hlkm = RegKey::predef(HKEY_LOCAL_MACHINE);
-        let key = hlkm.open_subkey(r"SYSTEM\CurrentControlSet\Control\FileSystem")?;
+#[derive(Default)]
+/// A list of checks
+pub struct Registry {
+    checks: Vec<Box<dyn Check>>,
+}
 
-        if key.get_value::<u32, _>("LongPathsEnabled")? == 0 {
-            Ok(LongPathsStatus::Disabled)
-        } else {
-            Ok(LongPathsStatus::Enabled)
-        }
+impl Registry {
+    #[must_use]
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Create a registry of every check `sfsu checkup` runs, with the limits from the config
+    pub fn builtin(config: &Config) -> Self {
+        Self::new()
+            .with(system::Git)
+            .with(system::MainBucket)
+            .with(system::DeveloperMode)
+            .with(system::LongPaths)
+            .with(system::Ntfs)
+            .with(system::Helpers)
+            .with(integrity::BrokenApps)
+            .with(integrity::EmptyApps)
+            .with(integrity::RemovedBuckets)
+            .with(integrity::BrokenShims)
+            .with(integrity::OrphanedPersist)
+            .with(buckets::Repositories)
+            .with(buckets::Branches)
+            .with(buckets::Changes)
+            .with(buckets::Origins)
+            .with(buckets::Manifests)
+            .with(shadowing::ShimsInPath)
+            .with(shadowing::ShadowedShims)
+            .with(shadowing::DuplicateShims)
+            .with(disk::CacheSize::new(&config.disk_usage))
+            .with(disk::LogsSize::new(&config.disk_usage))
+            .with(disk::OldVersionsSize::new(&config.disk_usage))
+    }
+
+    #[must_use]
+    /// Add a check to the registry
+    pub fn with(mut self, check: impl Check + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// List the checks in the registry
+    pub fn checks(&self) -> impl Iterator<Item = &dyn Check> {
+        self.checks.iter().map(AsRef::as_ref)
     }
 
-    /// Check if the user has developer mode enabled
+    /// Keep only the checks with the given ids, and remove the checks with the skipped ids
+    ///
+    /// If `only` is empty, every check that isn't skipped is kept
     ///
     /// # Errors
-    /// - Unable to read the registry
-    /// - Unable to read the value
-    pub fn get_windows_developer_status() -> windows::core::Result<bool> {
-        use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+    /// - An id does not match any check
+    pub fn filter(mut self, only: &[String], skip: &[String]) -> anyhow::Result<Self> {
+        if let Some(unknown) = only
+            .iter()
+            .chain(skip)
+            .find(|id| !self.checks().any(|check| check.id() == id.as_str()))
+        {
+            let ids = self.checks().map(Check::id).collect::<Vec<_>>().join(", ");
+            anyhow::bail!("No check with id \"{unknown}\". Valid checks are: {ids}");
+        }
 
-        let hlkm = RegKey::predef(HKEY_LOCAL_MACHINE);
-        let key = hlkm.open_subkey(r"SOFTWARE\Microsoft\Windows\CurrentVersion\AppModelUnlock")?;
+        self.checks.retain(|check| {
+            let id = check.id().to_string();
+            (only.is_empty() || only.contains(&id)) && !skip.contains(&id)
+        });
 
-        Ok(key.get_value::<u32, _>("AllowDevelopmentWithoutDevLicense")? == 1)
+        Ok(self)
     }
 
-    /// Check if the Scoop directory is on an NTFS filesystem
+    /// Run every check in the registry, in parallel
     ///
-    /// # Errors
-    /// - Unable to get the volume information
-    /// - Unable to check the filesystem
-    /// - Unable to get the root path
-    pub fn is_ntfs(ctx: &impl ScoopContext) -> windows::core::Result<bool> {
-        use windows::{
-            core::HSTRING,
-            Win32::{Foundation::MAX_PATH, Storage::FileSystem::GetVolumeInformationW},
-        };
+    /// The outcomes are in the same order as the checks in the registry
+    pub fn run(&self, provider: &dyn Provider) -> Vec<Outcome<'_>> {
+        self.checks
+            .par_iter()
+            .map(|check| {
+                let findings = check.run(provider);
+                debug!("Ran check: {}", check.id());
+
+                Outcome {
+                    check: check.as_ref(),
+                    findings,
+                }
+            })
+            .collect()
+    }
+}
 
-        let path = ctx.path();
+#[cfg(test)]
+mod tests {
+    use super::{fake::Fake, *};
 
-        let root = {
-            let mut current = path;
+    struct Always(&'static str, usize);
 
-            while let Some(parent) = current.parent() {
-                current = parent;
-            }
+    impl Check for Always {
+        fn id(&self) -> &'static str {
+            self.0
+        }
 
-            debug!("Checking filesystem of: {}", current.display());
+        fn title(&self) -> &'static str {
+            "Always finds problems"
+        }
 
-            current
-        };
+        fn severity(&self) -> Severity {
+            Severity::Warning
+        }
 
-        let mut fs_name = [0u16; MAX_PATH as usize];
-
-        unsafe {
-            GetVolumeInformationW(
-                &HSTRING::from(root),
-                None,
-                None,
-                // &mut max_component_length,
-                None,
-                // &mut flags,
-                None,
-                Some(&mut fs_name),
-            )?;
+        fn run(&self, _: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+            Ok((0..self.1).map(|i| Finding::new(i.to_string())).collect())
         }
+    }
 
-        debug!("Filesystem: {:?}", OsString::from_wide(&fs_name));
+    fn registry() -> Registry {
+        Registry::new()
+            .with(Always("one", 1))
+            .with(Always("two", 2))
+            .with(Always("three", 3))
+    }
 
-        Ok(fs_name.starts_with(&"NTFS".encode_utf16().collect_vec()))
+    fn ids(registry: &Registry) -> Vec<&str> {
+        registry.checks().map(Check::id).collect()
     }
 
-    #[must_use]
-    /// Check if the user has git installed, and in their path
-    pub fn git_installed() -> bool {
-        which::which("git").is_ok()
+    #[test]
+    fn test_filter() {
+        let only = registry().filter(&["two".to_string()], &[]).unwrap();
+        assert_eq!(ids(&only), vec!["two"]);
+
+        let skip = registry().filter(&[], &["two".to_string()]).unwrap();
+        assert_eq!(ids(&skip), vec!["one", "three"]);
+
+        assert!(registry().filter(&["four".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_run_keeps_order() {
+        let registry = registry();
+        let outcomes = registry.run(&Fake::default());
+
+        let counts = outcomes
+            .iter()
+            .map(|outcome| (outcome.check.id(), outcome.findings.as_ref().unwrap().len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(counts, vec![("one", 1), ("two", 2), ("three", 3)]);
+    }
+
+    #[test]
+    fn test_report() {
+        let registry = registry().filter(&[], &["three".to_string()]).unwrap();
+        let broken = Always("broken", 0);
+        let pass = Always("pass", 0);
+
+        let mut outcomes = registry.run(&Fake::default());
+        outcomes.push(Outcome {
+            check: &broken,
+            findings: Err(anyhow::anyhow!("no access")),
+        });
+
+        let report = outcomes
+            .iter()
+            .flat_map(Outcome::report)
+            .map(|entry| (entry.id, entry.status))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            report,
+            vec![
+                ("one", Status::Warn),
+                ("two", Status::Warn),
+                ("two", Status::Warn),
+                ("broken", Status::Skipped),
+            ]
+        );
+
+        let pass = Outcome {
+            check: &pass,
+            findings: Ok(vec![]),
+        };
+        let entry = &pass.report()[0];
+
+        assert_eq!(entry.status, Status::Pass);
+        assert_eq!(entry.message, "Always finds problems");
+    }
+
+    #[test]
+    fn test_builtin_ids_are_unique() {
+        let registry = Registry::builtin(&Config::default());
+        let mut ids = ids(&registry);
+        let count = ids.len();
+
+        ids.sort_unstable();
+        ids.dedup();
+
+        assert_eq!(ids.len(), count);
     }
 }
@@ -0,0 +1,375 @@
+//! Checks for the health of each bucket's git repository and manifests
+
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use sprinkles::packages::Manifest;
+
+use super::{Check, Finding, Provider, Severity};
+
+/// The most manifests that failed to parse to list by name, per bucket
+const MAX_LISTED_MANIFESTS: usize = 5;
+
+/// Check that each bucket is a git repository, with readable objects
+pub struct Repositories;
+
+impl Check for Repositories {
+    fn id(&self) -> &'static str {
+        "bucket-repositories"
+    }
+
+    fn title(&self) -> &'static str {
+        "Bucket repositories are intact"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(buckets(provider)?
+            .par_iter()
+            .filter_map(|(name, path)| {
+                let re_add = if known_source(provider, name).is_some() {
+                    format!("Run `scoop bucket rm {name}` and then `scoop bucket add {name}`")
+                } else {
+                    format!("Run `scoop bucket rm {name}` and then `scoop bucket add {name} <url>`")
+                };
+
+                if let Err(e) = provider.repo_health(path) {
+                    return Some(
+                        Finding::new(format!("{name} bucket is not a valid git repository: {e}"))
+                            .with_remedy(re_add),
+                    );
+                }
+
+                let corruption = provider.repo_corruption(path)?;
+
+                Some(
+                    Finding::new(format!(
+                        "{name} bucket's git objects are corrupt: {corruption}"
+                    ))
+                    .with_remedy(re_add),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Check that each bucket has its remote's default branch checked out
+pub struct Branches;
+
+impl Check for Branches {
+    fn id(&self) -> &'static str {
+        "bucket-branches"
+    }
+
+    fn title(&self) -> &'static str {
+        "Buckets are on their default branch"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(buckets(provider)?
+            .iter()
+            .filter_map(|(name, path)| {
+                let health = provider.repo_health(path).ok()?;
+                let default_branch = health.default_branch.as_deref();
+
+                match (health.branch.as_deref(), default_branch) {
+                    (None, Some(default_branch)) => Some(
+                        Finding::new(format!("{name} bucket has a detached HEAD"))
+                            .with_remedy(git(path, &format!("checkout {default_branch}"))),
+                    ),
+                    // Buckets may use `main` or `master`, so the default branch is looked up rather than guessed
+                    (None, None) => Some(
+                        Finding::new(format!("{name} bucket has a detached HEAD")).with_remedy(
+                            format!(
+                                "{}, then check out the branch it sets `origin/HEAD` to",
+                                git(path, "remote set-head origin --auto")
+                            ),
+                        ),
+                    ),
+                    (Some(branch), Some(default_branch)) if branch != default_branch => Some(
+                        Finding::new(format!(
+                            "{name} bucket is on the {branch} branch, rather than the remote's default branch {default_branch}"
+                        ))
+                        .with_remedy(git(path, &format!("checkout {default_branch}"))),
+                    ),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Check that buckets have no uncommitted changes
+pub struct Changes;
+
+impl Check for Changes {
+    fn id(&self) -> &'static str {
+        "bucket-changes"
+    }
+
+    fn title(&self) -> &'static str {
+        "Buckets have no uncommitted changes"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(buckets(provider)?
+            .iter()
+            .filter(|(_, path)| provider.repo_health(path).is_ok_and(|health| health.dirty))
+            .map(|(name, path)| {
+                Finding::new(format!(
+                    "{name} bucket has uncommitted changes, which may stop it from updating"
+                ))
+                .with_remedy(git(path, "stash"))
+            })
+            .collect())
+    }
+}
+
+/// Check that known buckets' origin is the known source
+pub struct Origins;
+
+impl Check for Origins {
+    fn id(&self) -> &'static str {
+        "bucket-origins"
+    }
+
+    fn title(&self) -> &'static str {
+        "Known buckets use their known source"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(buckets(provider)?
+            .iter()
+            .filter_map(|(name, path)| {
+                let known_source = known_source(provider, name)?;
+                let origin = provider.repo_health(path).ok()?.origin?;
+
+                if same_url(&origin, known_source) {
+                    return None;
+                }
+
+                Some(
+                    Finding::new(format!(
+                        "{name} bucket's origin is {origin}, rather than the known source {known_source}"
+                    ))
+                    .with_remedy(git(path, &format!("remote set-url origin {known_source}"))),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Check that every manifest in each bucket can be parsed
+pub struct Manifests;
+
+impl Check for Manifests {
+    fn id(&self) -> &'static str {
+        "bucket-manifests"
+    }
+
+    fn title(&self) -> &'static str {
+        "Bucket manifests can be parsed"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let mut findings = vec![];
+
+        for (name, path) in buckets(provider)? {
+            let manifests_path = path.join("bucket");
+
+            // Some buckets keep manifests in the root, rather than the `bucket` folder
+            let manifests_path = if provider.is_dir(&manifests_path) {
+                manifests_path
+            } else {
+                path
+            };
+
+            let broken = provider
+                .read_dir(&manifests_path)?
+                .into_par_iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter(|path| {
+                    provider
+                        .read_to_string(path)
+                        .ok()
+                        .and_then(|contents| serde_json::from_str::<Manifest>(&contents).ok())
+                        .is_none()
+                })
+                .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+                .collect::<Vec<_>>();
+
+            if broken.is_empty() {
+                continue;
+            }
+
+            let mut listed = broken.iter().sorted().take(MAX_LISTED_MANIFESTS).join(", ");
+            if broken.len() > MAX_LISTED_MANIFESTS {
+                listed += &format!(" and {} more", broken.len() - MAX_LISTED_MANIFESTS);
+            }
+
+            findings.push(
+                Finding::new(format!(
+                    "{} manifests in the {name} bucket failed to parse: {listed}",
+                    broken.len()
+                ))
+                .with_remedy(format!(
+                    "Run `sfsu update {name}` to get fixed manifests, or report them to the bucket's maintainers"
+                )),
+            );
+        }
+
+        Ok(findings)
+    }
+}
+
+/// List the added buckets' names and paths
+fn buckets(provider: &dyn Provider) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let buckets_path = &provider.paths().buckets;
+
+    if !provider.exists(buckets_path) {
+        return Ok(vec![]);
+    }
+
+    Ok(provider
+        .read_dir(buckets_path)?
+        .into_iter()
+        .filter(|path| provider.is_dir(path))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((name, path))
+        })
+        .collect())
+}
+
+fn known_source<'a>(provider: &'a dyn Provider, name: &str) -> Option<&'a str> {
+    provider
+        .known_buckets()
+        .iter()
+        .find(|(known_name, _)| known_name == name)
+        .map(|(_, source)| source.as_str())
+}
+
+fn git(path: &Path, args: &str) -> String {
+    format!("Run `git -C \"{}\" {args}`", path.display())
+}
+
+/// Compare remote urls, ignoring case, trailing slashes and the `.git` suffix
+fn same_url(a: &str, b: &str) -> bool {
+    fn normalise(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+
+        url.strip_suffix(".git").unwrap_or(url).to_lowercase()
+    }
+
+    normalise(a) == normalise(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diagnostics::fake::Fake, git::Health};
+
+    use super::*;
+
+    fn health(branch: Option<&str>, dirty: bool, origin: &str) -> Health {
+        Health {
+            branch: branch.map(ToString::to_string),
+            default_branch: Some("master".to_string()),
+            dirty,
+            origin: Some(origin.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_same_url() {
+        assert!(same_url(
+            "https://github.com/ScoopInstaller/Main",
+            "https://github.com/ScoopInstaller/Main.git"
+        ));
+        assert!(same_url(
+            "https://github.com/scoopinstaller/main/",
+            "https://github.com/ScoopInstaller/Main"
+        ));
+        assert!(!same_url(
+            "https://github.com/someone/Main",
+            "https://github.com/ScoopInstaller/Main"
+        ));
+    }
+
+    #[test]
+    fn test_repo_checks() {
+        let mut fake = Fake::default();
+        let buckets = fake.paths().buckets.clone();
+
+        fake.add_known_bucket("main", "https://github.com/ScoopInstaller/Main");
+        fake.add_repo(
+            buckets.join("main"),
+            health(Some("master"), false, "https://github.com/someone/Main"),
+        );
+        fake.add_repo(
+            buckets.join("extras"),
+            health(None, true, "https://github.com/ScoopInstaller/Extras"),
+        );
+        fake.add_dir(buckets.join("broken"));
+
+        let repositories = Repositories.run(&fake).unwrap();
+        assert_eq!(repositories.len(), 1);
+        assert!(repositories[0].message.starts_with("broken"));
+
+        let branches = Branches.run(&fake).unwrap();
+        assert_eq!(branches.len(), 1);
+        assert!(branches[0].message.starts_with("extras"));
+        assert!(branches[0]
+            .remedy
+            .as_ref()
+            .is_some_and(|remedy| remedy.ends_with("checkout master`")));
+
+        let changes = Changes.run(&fake).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].message.starts_with("extras"));
+
+        let origins = Origins.run(&fake).unwrap();
+        assert_eq!(origins.len(), 1);
+        assert!(origins[0].message.starts_with("main"));
+    }
+
+    #[test]
+    fn test_detached_without_default_branch() {
+        let mut fake = Fake::default();
+        let buckets = fake.paths().buckets.clone();
+
+        fake.add_repo(
+            buckets.join("extras"),
+            Health {
+                default_branch: None,
+                ..health(None, false, "https://github.com/ScoopInstaller/Extras")
+            },
+        );
+
+        let branches = Branches.run(&fake).unwrap();
+        assert_eq!(branches.len(), 1);
+
+        let remedy = branches[0].remedy.as_deref().unwrap();
+        assert!(remedy.contains("remote set-head origin --auto"));
+        assert!(!remedy.contains("checkout"));
+    }
+}
@@ -0,0 +1,287 @@
+//! Checks for the disk space used by the cache, logs and old app versions
+
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use sprinkles::packages::Manifest;
+
+use crate::{config::DiskUsage, wrappers::sizes::Size};
+
+use super::{
+    integrity::{dirs, file_name, parse},
+    Check, Finding, Provider, Severity,
+};
+
+/// The most apps to list by name when old versions use too much space
+const MAX_LISTED_APPS: usize = 5;
+
+/// Check that the download cache is below its size limit
+pub struct CacheSize {
+    limit: Size,
+}
+
+impl CacheSize {
+    #[must_use]
+    /// Create the check, with the limit from the config
+    pub fn new(config: &DiskUsage) -> Self {
+        Self {
+            limit: mib(config.cache),
+        }
+    }
+}
+
+impl Check for CacheSize {
+    fn id(&self) -> &'static str {
+        "cache-size"
+    }
+
+    fn title(&self) -> &'static str {
+        "The download cache is a reasonable size"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let cache = &provider.paths().cache;
+
+        if !provider.exists(cache) {
+            return Ok(vec![]);
+        }
+
+        // Only count downloads, named `app#version#url`, as sfsu keeps its own data in the cache too,
+        // which `sfsu cache rm` doesn't remove
+        let size = provider
+            .read_dir(cache)?
+            .iter()
+            .filter(|path| !provider.is_dir(path) && file_name(path).contains('#'))
+            .try_fold(Size::new(0), |size, path| {
+                Ok::<_, std::io::Error>(size + provider.size(path)?)
+            })?;
+
+        if size <= self.limit {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Finding::new(format!(
+            "The download cache is using {size}, which is over the {} limit",
+            self.limit
+        ))
+        .with_remedy("Run `sfsu cache rm '*'` to clear it")])
+    }
+}
+
+/// Check that sfsu's logs are below their size limit
+pub struct LogsSize {
+    limit: Size,
+}
+
+impl LogsSize {
+    #[must_use]
+    /// Create the check, with the limit from the config
+    pub fn new(config: &DiskUsage) -> Self {
+        Self {
+            limit: mib(config.logs),
+        }
+    }
+}
+
+impl Check for LogsSize {
+    fn id(&self) -> &'static str {
+        "logs-size"
+    }
+
+    fn title(&self) -> &'static str {
+        "sfsu's logs are a reasonable size"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let Some(logs) = &provider.paths().logs else {
+            anyhow::bail!("Unable to find the logs directory");
+        };
+
+        if !provider.exists(logs) {
+            return Ok(vec![]);
+        }
+
+        let size = provider.size(logs)?;
+
+        if size <= self.limit {
+            return Ok(vec![]);
+        }
+
+        // sfsu only keeps its 10 most recent logs, so large logs are usually from verbose runs
+        Ok(vec![Finding::new(format!(
+            "sfsu's logs are using {size}, which is over the {} limit",
+            self.limit
+        ))
+        .with_remedy(format!(
+            "Run `Remove-Item \"{}\\*\"` to delete them",
+            logs.display()
+        ))])
+    }
+}
+
+/// Check that installed apps' old versions are below their combined size limit
+pub struct OldVersionsSize {
+    limit: Size,
+}
+
+impl OldVersionsSize {
+    #[must_use]
+    /// Create the check, with the limit from the config
+    pub fn new(config: &DiskUsage) -> Self {
+        Self {
+            limit: mib(config.old_versions),
+        }
+    }
+}
+
+impl Check for OldVersionsSize {
+    fn id(&self) -> &'static str {
+        "old-versions-size"
+    }
+
+    fn title(&self) -> &'static str {
+        "Old app versions are a reasonable size"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let mut sizes = vec![];
+
+        for app_path in dirs(provider, &provider.paths().apps)? {
+            let size = old_versions(provider, &app_path)?
+                .iter()
+                .try_fold(Size::new(0), |size, path| {
+                    Ok::<_, std::io::Error>(size + provider.size(path)?)
+                })?;
+
+            if size > Size::new(0) {
+                sizes.push((file_name(&app_path), size));
+            }
+        }
+
+        let total = sizes
+            .iter()
+            .fold(Size::new(0), |total, (_, size)| total + *size);
+
+        if total <= self.limit {
+            return Ok(vec![]);
+        }
+
+        let mut largest = sizes
+            .iter()
+            .sorted_by(|(_, a), (_, b)| b.cmp(a))
+            .take(MAX_LISTED_APPS)
+            .map(|(name, size)| format!("{name} ({size})"))
+            .join(", ");
+        if sizes.len() > MAX_LISTED_APPS {
+            largest += &format!(" and {} more", sizes.len() - MAX_LISTED_APPS);
+        }
+
+        Ok(vec![Finding::new(format!(
+            "Old app versions are using {total}, which is over the {} limit: {largest}",
+            self.limit
+        ))
+        .with_remedy("Run `scoop cleanup --all` to remove them")])
+    }
+}
+
+/// List an app's installed versions, other than the current one
+///
+/// Apps without a readable manifest are skipped, as the current version can't be known
+fn old_versions(provider: &dyn Provider, app_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let Some(manifest) = parse::<Manifest>(provider, &app_path.join("current/manifest.json"))
+    else {
+        return Ok(vec![]);
+    };
+
+    let current = manifest.version.to_string();
+
+    Ok(dirs(provider, app_path)?
+        .into_iter()
+        .filter(|path| {
+            let name = file_name(path);
+            name != "current" && name != current
+        })
+        .collect())
+}
+
+/// Convert a size in MiB, as it is written in the config, to a [`Size`]
+fn mib(size: u64) -> Size {
+    Size::new(size.saturating_mul(1024 * 1024))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::diagnostics::fake::Fake;
+
+    use super::*;
+
+    fn limits(size: u64) -> DiskUsage {
+        DiskUsage {
+            cache: size,
+            logs: size,
+            old_versions: size,
+        }
+    }
+
+    #[test]
+    fn test_cache_size() {
+        let mut fake = Fake::default();
+        let cache = fake.paths().cache.clone();
+        fake.add_file(
+            cache.join("app#1.0.0#file.zip"),
+            "a".repeat(2 * 1024 * 1024),
+        );
+
+        // sfsu's own data isn't removed by `sfsu cache rm`, so it isn't counted
+        fake.add_file(
+            cache.join("sfsu-search-index/main.json"),
+            "a".repeat(2 * 1024 * 1024),
+        );
+        fake.add_dir(cache.join("sfsu-known-buckets/extras"));
+
+        assert!(CacheSize::new(&limits(3)).run(&fake).unwrap().is_empty());
+
+        let findings = CacheSize::new(&limits(1)).run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "The download cache is using 2.00 MiB, which is over the 1.00 MiB limit"
+        );
+    }
+
+    #[test]
+    fn test_old_versions_size() {
+        let mut fake = Fake::default();
+        let app = fake.paths().apps.join("app");
+
+        fake.install_app("app", json!({ "version": "2.0.0" }));
+        fake.add_file(app.join("2.0.0/app.exe"), "a".repeat(4 * 1024 * 1024));
+        fake.add_file(app.join("1.0.0/app.exe"), "a".repeat(2 * 1024 * 1024));
+
+        let findings = OldVersionsSize::new(&limits(1)).run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "Old app versions are using 2.00 MiB, which is over the 1.00 MiB limit: app (2.00 MiB)"
+        );
+
+        assert!(OldVersionsSize::new(&limits(2))
+            .run(&fake)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! A fake system for testing checks, with an in-memory filesystem
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::{Path, PathBuf},
+};
+
+use parking_lot::Mutex;
+
+use crate::{git::Health, wrappers::sizes::Size};
+
+use super::{provider::Paths, LongPathsStatus, Provider};
+
+pub struct Fake {
+    paths: Paths,
+    known_buckets: Vec<(String, String)>,
+    dirs: Mutex<BTreeSet<PathBuf>>,
+    files: Mutex<BTreeMap<PathBuf, String>>,
+    executables: BTreeMap<String, PathBuf>,
+    path_dirs: Vec<PathBuf>,
+    path_extensions: Vec<String>,
+    repos: BTreeMap<PathBuf, Health>,
+    long_paths: LongPathsStatus,
+    developer_mode: bool,
+    filesystem: String,
+}
+
+impl Default for Fake {
+    fn default() -> Self {
+        let root = PathBuf::from("scoop");
+
+        Self {
+            paths: Paths {
+                apps: root.join("apps"),
+                buckets: root.join("buckets"),
+                persist: root.join("persist"),
+                cache: root.join("cache"),
+                logs: Some(root.join("logs")),
+                shims: root.join("shims"),
+                root,
+            },
+            known_buckets: vec![],
+            dirs: Mutex::default(),
+            files: Mutex::default(),
+            executables: BTreeMap::new(),
+            path_dirs: vec![],
+            path_extensions: vec![".EXE".to_string(), ".CMD".to_string()],
+            repos: BTreeMap::new(),
+            long_paths: LongPathsStatus::Enabled,
+            developer_mode: true,
+            filesystem: "NTFS".to_string(),
+        }
+    }
+}
+
+impl Fake {
+    /// Add a directory, and its parents
+    pub fn add_dir(&mut self, path: impl AsRef<Path>) {
+        let mut dirs = self.dirs.lock();
+
+        for ancestor in path.as_ref().ancestors() {
+            if ancestor.as_os_str().is_empty() {
+                break;
+            }
+
+            dirs.insert(ancestor.to_path_buf());
+        }
+    }
+
+    /// Add a file, and its parent directories
+    pub fn add_file(&mut self, path: impl AsRef<Path>, contents: impl Into<String>) {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            self.add_dir(parent);
+        }
+
+        self.files
+            .lock()
+            .insert(path.to_path_buf(), contents.into());
+    }
+
+    /// Add an executable to the fake `PATH`
+    pub fn add_executable(&mut self, exe: &str) {
+        self.executables
+            .insert(exe.to_string(), PathBuf::from("bin").join(exe));
+    }
+
+    /// Add a directory to the end of the fake `PATH`
+    pub fn add_path_dir(&mut self, path: impl AsRef<Path>) {
+        self.add_dir(&path);
+        self.path_dirs.push(path.as_ref().to_path_buf());
+    }
+
+    /// Add a git repository
+    pub fn add_repo(&mut self, path: impl AsRef<Path>, health: Health) {
+        self.add_dir(&path);
+        self.repos.insert(path.as_ref().to_path_buf(), health);
+    }
+
+    /// Set the status of long paths
+    pub fn set_long_paths(&mut self, status: LongPathsStatus) {
+        self.long_paths = status;
+    }
+
+    /// Set whether developer mode is enabled
+    pub fn set_developer_mode(&mut self, enabled: bool) {
+        self.developer_mode = enabled;
+    }
+
+    /// Set the name of the filesystem every path is on
+    pub fn set_filesystem(&mut self, filesystem: &str) {
+        self.filesystem = filesystem.to_string();
+    }
+
+    /// Install an app, with a valid manifest that has `overrides` merged into it
+    ///
+    /// Returns the path to the app's current version
+    pub fn install_app(&mut self, name: &str, overrides: serde_json::Value) -> PathBuf {
+        let mut manifest = serde_json::json!({
+            "version": "1.0.0",
+            "description": name,
+            "homepage": "https://example.com",
+            "license": "MIT",
+            "url": format!("https://example.com/{name}.zip"),
+            "hash": "50b04b55425b5c465d076cdb184f63a0cd0f86f6ec8bb4d5860114a713d2c29a",
+        });
+
+        if let (Some(manifest), serde_json::Value::Object(overrides)) =
+            (manifest.as_object_mut(), overrides)
+        {
+            manifest.extend(overrides);
+        }
+
+        let current = self.paths.apps.join(name).join("current");
+        self.add_file(current.join("manifest.json"), manifest.to_string());
+
+        current
+    }
+
+    /// Add a known bucket
+    pub fn add_known_bucket(&mut self, name: &str, source: &str) {
+        self.known_buckets
+            .push((name.to_string(), source.to_string()));
+    }
+}
+
+fn not_found() -> io::Error {
+    io::Error::from(io::ErrorKind::NotFound)
+}
+
+impl Provider for Fake {
+    fn paths(&self) -> &Paths {
+        &self.paths
+    }
+
+    fn known_buckets(&self) -> &[(String, String)] {
+        &self.known_buckets
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.is_dir(path) {
+            return Err(not_found());
+        }
+
+        let dirs = self.dirs.lock();
+        let files = self.files.lock();
+
+        Ok(dirs
+            .iter()
+            .chain(files.keys())
+            .filter(|entry| entry.parent() == Some(path))
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect())
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs.lock().contains(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_dir(path) || self.files.lock().contains_key(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files.lock().get(path).cloned().ok_or_else(not_found)
+    }
+
+    fn size(&self, path: &Path) -> io::Result<Size> {
+        if !self.exists(path) {
+            return Err(not_found());
+        }
+
+        Ok(Size::new(
+            self.files
+                .lock()
+                .iter()
+                .filter(|(file, _)| file.starts_with(path))
+                .map(|(_, contents)| contents.len() as u64)
+                .sum(),
+        ))
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        if !self.is_dir(path) {
+            return Err(not_found());
+        }
+
+        self.dirs.lock().retain(|dir| !dir.starts_with(path));
+        self.files.lock().retain(|file, _| !file.starts_with(path));
+
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.files
+            .lock()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(not_found)
+    }
+
+    fn which(&self, exe: &str) -> Option<PathBuf> {
+        self.executables.get(exe).cloned()
+    }
+
+    fn path_dirs(&self) -> &[PathBuf] {
+        &self.path_dirs
+    }
+
+    fn path_extensions(&self) -> &[String] {
+        &self.path_extensions
+    }
+
+    fn long_paths(&self) -> anyhow::Result<LongPathsStatus> {
+        Ok(self.long_paths)
+    }
+
+    fn developer_mode(&self) -> anyhow::Result<bool> {
+        Ok(self.developer_mode)
+    }
+
+    fn filesystem(&self, _: &Path) -> anyhow::Result<String> {
+        Ok(self.filesystem.clone())
+    }
+
+    fn repo_health(&self, path: &Path) -> anyhow::Result<Health> {
+        self.repos
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("not a git repository"))
+    }
+
+    fn repo_corruption(&self, path: &Path) -> Option<String> {
+        (!self.repos.contains_key(path)).then(|| "not a git repository".to_string())
+    }
+}
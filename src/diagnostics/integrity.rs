@@ -0,0 +1,540 @@
+//! Checks for broken installs, and leftovers from apps that are no longer installed
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use sprinkles::packages::{InstallManifest, Manifest};
+
+use super::{Check, Finding, Provider, Severity};
+
+/// Check that installed apps have a current version, a manifest and install info
+pub struct BrokenApps;
+
+impl Check for BrokenApps {
+    fn id(&self) -> &'static str {
+        "broken-apps"
+    }
+
+    fn title(&self) -> &'static str {
+        "Installed apps are intact"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let mut findings = vec![];
+
+        for app_path in dirs(provider, &provider.paths().apps)? {
+            let name = file_name(&app_path);
+            let current = app_path.join("current");
+
+            if !provider.exists(&current) {
+                // Apps with no versions at all are left to `EmptyApps`
+                if !versions(provider, &app_path)?.is_empty() {
+                    findings.push(
+                        Finding::new(format!("{name} has no current version")).with_remedy(
+                            format!("Run `scoop reset {name}` to link the installed version"),
+                        ),
+                    );
+                }
+
+                continue;
+            }
+
+            let reinstall = format!(
+                "Run `scoop uninstall {name}` and then `scoop install {name}` to reinstall it"
+            );
+
+            if parse::<Manifest>(provider, &current.join("manifest.json")).is_none() {
+                findings.push(
+                    Finding::new(format!(
+                        "{name} is missing its manifest. The install may have failed"
+                    ))
+                    .with_remedy(reinstall),
+                );
+            } else if parse::<InstallManifest>(provider, &current.join("install.json")).is_none() {
+                findings.push(
+                    Finding::new(format!(
+                        "{name} is missing its install info. The install may have failed"
+                    ))
+                    .with_remedy(reinstall),
+                );
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+/// Check for app folders with no installed versions
+pub struct EmptyApps;
+
+impl Check for EmptyApps {
+    fn id(&self) -> &'static str {
+        "empty-apps"
+    }
+
+    fn title(&self) -> &'static str {
+        "Every app folder has an installed version"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let mut findings = vec![];
+
+        for app_path in dirs(provider, &provider.paths().apps)? {
+            // `current` is a junction, so it doesn't exist if the version it points to was removed
+            if provider.exists(&app_path.join("current"))
+                || !versions(provider, &app_path)?.is_empty()
+            {
+                continue;
+            }
+
+            findings.push(
+                Finding::new(format!(
+                    "{} has no installed versions",
+                    file_name(&app_path)
+                ))
+                .with_remedy("Run `sfsu checkup --fix` to remove its empty folder")
+                .with_path(app_path),
+            );
+        }
+
+        Ok(findings)
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn fix(&self, provider: &dyn Provider, finding: &Finding) -> anyhow::Result<()> {
+        if let Some(path) = &finding.path {
+            provider.remove_dir_all(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that the buckets apps were installed from are still added
+pub struct RemovedBuckets;
+
+impl Check for RemovedBuckets {
+    fn id(&self) -> &'static str {
+        "removed-buckets"
+    }
+
+    fn title(&self) -> &'static str {
+        "Installed apps' buckets are added"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let buckets_path = &provider.paths().buckets;
+        let mut findings = vec![];
+
+        for app_path in dirs(provider, &provider.paths().apps)? {
+            let name = file_name(&app_path);
+            let install_path = app_path.join("current").join("install.json");
+
+            // Apps installed from a url or file have no bucket
+            let Some(bucket) = parse::<InstallManifest>(provider, &install_path)
+                .and_then(|install_manifest| install_manifest.bucket)
+            else {
+                continue;
+            };
+
+            if !provider.is_dir(&buckets_path.join(&bucket)) {
+                findings.push(
+                    Finding::new(format!(
+                        "{name} was installed from the {bucket} bucket, which has been removed"
+                    ))
+                    .with_remedy(format!(
+                        "Run `scoop bucket add {bucket}` so that {name} can be updated"
+                    )),
+                );
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+/// Check that shims point to executables that exist
+pub struct BrokenShims;
+
+impl Check for BrokenShims {
+    fn id(&self) -> &'static str {
+        "broken-shims"
+    }
+
+    fn title(&self) -> &'static str {
+        "Shims point to executables that exist"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let shims_path = &provider.paths().shims;
+
+        if !provider.exists(shims_path) {
+            return Ok(vec![]);
+        }
+
+        // Scoop creates several files for each shim, which all point to the same target,
+        // so each shim is only reported once
+        let shims = provider
+            .read_dir(shims_path)?
+            .into_iter()
+            .filter_map(|path| {
+                let target = shim_file_target(provider, &path)?;
+
+                Some((shim_name(&path).to_lowercase(), (path, target)))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(shims
+            .into_values()
+            .filter(|(_, target)| !provider.exists(target))
+            .map(|(path, target)| {
+                Finding::new(format!(
+                    "Shim {} points to {}, which does not exist",
+                    shim_name(&path),
+                    target.display()
+                ))
+                .with_remedy("Run `sfsu checkup --fix` to remove the shim")
+                .with_path(&path)
+            })
+            .collect())
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn fix(&self, provider: &dyn Provider, finding: &Finding) -> anyhow::Result<()> {
+        let Some(path) = &finding.path else {
+            return Ok(());
+        };
+
+        let name = shim_name(path);
+
+        // Every file of the shim is removed together, i.e the `.shim` file and the `.exe` it configures
+        for sibling in provider.read_dir(&provider.paths().shims)? {
+            if shim_name(&sibling).eq_ignore_ascii_case(&name) && !provider.is_dir(&sibling) {
+                provider.remove_file(&sibling)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The extensions of the files Scoop creates for shims
+const SHIM_EXTENSIONS: &[&str] = &["shim", "exe", "cmd", "ps1"];
+
+/// The name of the shim a file in the shims directory belongs to
+fn shim_name(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    match extension {
+        Some(ext) if SHIM_EXTENSIONS.contains(&ext.as_str()) => path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        // Shims for scripts include an extensionless shell script, whose name may still contain a dot
+        _ => file_name(path),
+    }
+}
+
+/// Read the path a file in the shims directory points to
+///
+/// `.exe` shims are configured by the `.shim` file next to them,
+/// while the `.cmd`, `.ps1` and extensionless shims Scoop creates for scripts start with a comment containing the target
+fn shim_file_target(provider: &dyn Provider, path: &Path) -> Option<PathBuf> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    if extension.as_deref() == Some("exe") || provider.is_dir(path) {
+        return None;
+    }
+
+    let contents = provider.read_to_string(path).ok()?;
+
+    match extension.as_deref() {
+        Some("shim") => shim_target(&contents),
+        Some("cmd") => comment_target(&contents, "@rem "),
+        _ => comment_target(&contents, "# "),
+    }
+}
+
+/// Read the target from the comment at the top of a script shim, after the shebang if there is one
+fn comment_target(contents: &str, prefix: &str) -> Option<PathBuf> {
+    let target = contents
+        .lines()
+        .find(|line| !line.starts_with("#!"))?
+        .strip_prefix(prefix)?
+        .trim();
+
+    (!target.is_empty()).then(|| PathBuf::from(target))
+}
+
+/// Check for persist folders of apps that aren't installed
+pub struct OrphanedPersist;
+
+impl Check for OrphanedPersist {
+    fn id(&self) -> &'static str {
+        "orphaned-persist"
+    }
+
+    fn title(&self) -> &'static str {
+        "Every persist folder belongs to an installed app"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        let paths = provider.paths();
+
+        Ok(dirs(provider, &paths.persist)?
+            .into_iter()
+            .map(|path| file_name(&path))
+            .filter(|name| !provider.exists(&paths.apps.join(name)))
+            .map(|name| {
+                Finding::new(format!("{name} has a persist folder, but is not installed"))
+                    .with_remedy(
+                    "Run `sfsu app purge` to remove persist folders for apps that aren't installed",
+                )
+            })
+            .collect())
+    }
+}
+
+/// Parse a JSON file, returning [`None`] if it is missing or invalid
+pub(super) fn parse<T: serde::de::DeserializeOwned>(
+    provider: &dyn Provider,
+    path: &Path,
+) -> Option<T> {
+    serde_json::from_str(&provider.read_to_string(path).ok()?).ok()
+}
+
+/// Read the path a shim points to from its `.shim` file
+pub(super) fn shim_target(contents: &str) -> Option<PathBuf> {
+    contents.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+
+        (key.trim() == "path").then(|| PathBuf::from(value.trim().trim_matches('"')))
+    })
+}
+
+/// List the directories in `path`, or nothing if `path` doesn't exist
+pub(super) fn dirs(provider: &dyn Provider, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !provider.exists(path) {
+        return Ok(vec![]);
+    }
+
+    Ok(provider
+        .read_dir(path)?
+        .into_iter()
+        .filter(|path| provider.is_dir(path))
+        .collect())
+}
+
+/// List the installed versions of an app, excluding `current`
+fn versions(provider: &dyn Provider, app_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    Ok(dirs(provider, app_path)?
+        .into_iter()
+        .filter(|path| !path.ends_with("current"))
+        .collect())
+}
+
+pub(super) fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::diagnostics::fake::Fake;
+
+    use super::*;
+
+    fn install_from_bucket(fake: &mut Fake, name: &str, bucket: &str) {
+        let current = fake.install_app(name, json!({}));
+
+        fake.add_file(
+            current.join("install.json"),
+            format!(r#"{{ "bucket": "{bucket}" }}"#),
+        );
+    }
+
+    #[test]
+    fn test_shim_target() {
+        let contents =
+            "path = \"C:\\Users\\me\\scoop\\apps\\git\\current\\bin\\git.exe\"\nargs = --version\n";
+
+        assert_eq!(
+            shim_target(contents),
+            Some(PathBuf::from(
+                "C:\\Users\\me\\scoop\\apps\\git\\current\\bin\\git.exe"
+            ))
+        );
+        assert_eq!(shim_target("args = --version"), None);
+    }
+
+    #[test]
+    fn test_comment_target() {
+        let target = "C:\\Users\\me\\scoop\\apps\\tool\\current\\tool.ps1";
+
+        assert_eq!(
+            comment_target(&format!("@rem {target}\n@echo off\n"), "@rem "),
+            Some(PathBuf::from(target))
+        );
+        assert_eq!(
+            comment_target(&format!("#!/bin/sh\n# {target}\n"), "# "),
+            Some(PathBuf::from(target))
+        );
+        assert_eq!(comment_target("@echo off\n", "@rem "), None);
+    }
+
+    #[test]
+    fn test_broken_apps() {
+        let mut fake = Fake::default();
+        install_from_bucket(&mut fake, "git", "main");
+
+        // Installed, but the install failed before writing install info
+        fake.install_app("broken", json!({}));
+        let apps = fake.paths().apps.clone();
+        // Has a version, but `current` is missing
+        fake.add_dir(apps.join("unlinked/1.0.0"));
+
+        let findings = BrokenApps.run(&fake).unwrap();
+        let messages = findings
+            .iter()
+            .map(|finding| finding.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                "broken is missing its install info. The install may have failed",
+                "unlinked has no current version",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_apps_fix() {
+        let mut fake = Fake::default();
+        let empty = fake.paths().apps.join("empty");
+        fake.add_dir(&empty);
+
+        let findings = EmptyApps.run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+
+        EmptyApps.fix(&fake, &findings[0]).unwrap();
+
+        assert!(!fake.exists(&empty));
+        assert!(EmptyApps.run(&fake).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_removed_buckets() {
+        let mut fake = Fake::default();
+        fake.add_dir(fake.paths().buckets.join("main"));
+        install_from_bucket(&mut fake, "git", "main");
+        install_from_bucket(&mut fake, "vscode", "extras");
+
+        let findings = RemovedBuckets.run(&fake).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.starts_with("vscode"));
+    }
+
+    #[test]
+    fn test_broken_shims_fix() {
+        let mut fake = Fake::default();
+        let shims = fake.paths().shims.clone();
+        let target = fake.paths().apps.join("git/current/bin/git.exe");
+
+        fake.add_file(
+            shims.join("git.shim"),
+            format!("path = \"{}\"", target.display()),
+        );
+        fake.add_file(shims.join("git.exe"), "");
+
+        let findings = BrokenShims.run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+
+        fake.add_file(&target, "");
+        assert!(BrokenShims.run(&fake).unwrap().is_empty());
+
+        BrokenShims.fix(&fake, &findings[0]).unwrap();
+        assert!(!fake.exists(&shims.join("git.shim")));
+        assert!(!fake.exists(&shims.join("git.exe")));
+    }
+
+    #[test]
+    fn test_broken_script_shims_fix() {
+        let mut fake = Fake::default();
+        let shims = fake.paths().shims.clone();
+        let target = fake.paths().apps.join("tool/current/tool.ps1");
+        let target = target.display();
+
+        fake.add_file(
+            shims.join("tool.ps1"),
+            format!("# {target}\n& $path @args\n"),
+        );
+        fake.add_file(
+            shims.join("tool.cmd"),
+            format!("@rem {target}\n@echo off\n"),
+        );
+        fake.add_file(shims.join("tool"), format!("#!/bin/sh\n# {target}\n"));
+        fake.add_file(shims.join("tools.cmd"), "@echo off\n");
+
+        let findings = BrokenShims.run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.starts_with("Shim tool points to"));
+
+        BrokenShims.fix(&fake, &findings[0]).unwrap();
+        assert!(!fake.exists(&shims.join("tool.ps1")));
+        assert!(!fake.exists(&shims.join("tool.cmd")));
+        assert!(!fake.exists(&shims.join("tool")));
+        assert!(fake.exists(&shims.join("tools.cmd")));
+    }
+
+    #[test]
+    fn test_orphaned_persist() {
+        let mut fake = Fake::default();
+        install_from_bucket(&mut fake, "git", "main");
+        fake.add_dir(fake.paths().persist.join("git"));
+        fake.add_dir(fake.paths().persist.join("vscode"));
+
+        let findings = OrphanedPersist.run(&fake).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.starts_with("vscode"));
+    }
+}
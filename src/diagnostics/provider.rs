@@ -0,0 +1,341 @@
+//! Access to the system for checks
+//!
+//! Checks never touch the filesystem, registry or git directly, so that they can be run against a fake system in tests.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use sprinkles::contexts::ScoopContext;
+
+use crate::{git::Health, wrappers::sizes::Size};
+
+use super::LongPathsStatus;
+
+#[derive(Debug, Clone, Default)]
+/// The paths of the Scoop directories checks look at
+pub struct Paths {
+    /// The Scoop root directory
+    pub root: PathBuf,
+    /// The installed apps
+    pub apps: PathBuf,
+    /// The added buckets
+    pub buckets: PathBuf,
+    /// The persisted app data
+    pub persist: PathBuf,
+    /// The download cache
+    pub cache: PathBuf,
+    /// sfsu's logs, if the directory could be found
+    pub logs: Option<PathBuf>,
+    /// The shims for installed apps' binaries
+    pub shims: PathBuf,
+}
+
+impl Paths {
+    #[must_use]
+    /// Get the paths for the given context
+    pub fn new(ctx: &impl ScoopContext) -> Self {
+        Self {
+            root: ctx.path().to_path_buf(),
+            apps: ctx.apps_path(),
+            buckets: ctx.buckets_path(),
+            persist: ctx.persist_path(),
+            cache: ctx.cache_path(),
+            logs: ctx.logging_dir().ok(),
+            shims: ctx.path().join("shims"),
+        }
+    }
+}
+
+/// Access to the system for checks
+pub trait Provider: Sync {
+    /// The paths of the Scoop directories
+    fn paths(&self) -> &Paths;
+
+    /// The known buckets, as `(name, source)` pairs
+    fn known_buckets(&self) -> &[(String, String)];
+
+    /// List the paths of the entries in a directory
+    ///
+    /// # Errors
+    /// - The directory could not be read
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Check if the path is a directory
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// Check if the path exists
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Read a file to a string
+    ///
+    /// # Errors
+    /// - The file could not be read
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Get the size of a file, or the combined size of every file in a directory
+    ///
+    /// Symlinks and junctions are not followed
+    ///
+    /// # Errors
+    /// - The file or directory could not be read
+    fn size(&self, path: &Path) -> io::Result<Size>;
+
+    /// Remove a directory and its contents
+    ///
+    /// # Errors
+    /// - The directory could not be removed
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// Remove a file
+    ///
+    /// # Errors
+    /// - The file could not be removed
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    /// Find an executable in the `PATH`
+    fn which(&self, exe: &str) -> Option<PathBuf>;
+
+    /// The directories in the `PATH`, in the order they are searched
+    fn path_dirs(&self) -> &[PathBuf];
+
+    /// The extensions tried when searching the `PATH` for an executable, i.e `.EXE`
+    fn path_extensions(&self) -> &[String];
+
+    /// Check if long paths are enabled
+    ///
+    /// # Errors
+    /// - Unable to read the registry or the OS version
+    fn long_paths(&self) -> anyhow::Result<LongPathsStatus>;
+
+    /// Check if the user has developer mode enabled
+    ///
+    /// # Errors
+    /// - Unable to read the registry
+    fn developer_mode(&self) -> anyhow::Result<bool>;
+
+    /// Get the name of the filesystem the path is on, i.e `NTFS`
+    ///
+    /// # Errors
+    /// - Unable to get the volume information
+    fn filesystem(&self, path: &Path) -> anyhow::Result<String>;
+
+    /// Inspect the branches and working tree of the git repository at `path`
+    ///
+    /// # Errors
+    /// - The repository could not be opened or inspected
+    fn repo_health(&self, path: &Path) -> anyhow::Result<Health>;
+
+    /// Check the objects of the git repository at `path` can be read
+    ///
+    /// Returns why the objects could not be read, or [`None`] if they could
+    fn repo_corruption(&self, path: &Path) -> Option<String>;
+}
+
+/// The extensions Windows tries when `PATHEXT` isn't set
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// The real system
+pub struct System {
+    paths: Paths,
+    known_buckets: Vec<(String, String)>,
+    path_dirs: Vec<PathBuf>,
+    path_extensions: Vec<String>,
+}
+
+impl System {
+    #[must_use]
+    /// Create a provider for the system, in the given context
+    pub fn new(ctx: &impl ScoopContext) -> Self {
+        let known_buckets = ctx
+            .known_buckets()
+            .into_iter()
+            .map(|(name, source)| ((*name).to_string(), (*source).to_string()))
+            .collect();
+
+        let path_dirs = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+
+        let path_extensions = std::env::var("PATHEXT")
+            .unwrap_or_else(|_| DEFAULT_PATHEXT.to_string())
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        Self {
+            paths: Paths::new(ctx),
+            known_buckets,
+            path_dirs,
+            path_extensions,
+        }
+    }
+}
+
+impl Provider for System {
+    fn paths(&self) -> &Paths {
+        &self.paths
+    }
+
+    fn known_buckets(&self) -> &[(String, String)] {
+        &self.known_buckets
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| Ok(entry?.path()))
+            .collect()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn size(&self, path: &Path) -> io::Result<Size> {
+        let metadata = std::fs::symlink_metadata(path)?;
+
+        if metadata.is_symlink() {
+            return Ok(Size::new(0));
+        }
+
+        if !metadata.is_dir() {
+            return Ok(Size::new(metadata.len()));
+        }
+
+        std::fs::read_dir(path)?.try_fold(Size::new(0), |size, entry| {
+            Ok(size + self.size(&entry?.path())?)
+        })
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn which(&self, exe: &str) -> Option<PathBuf> {
+        which::which(exe).ok()
+    }
+
+    fn path_dirs(&self) -> &[PathBuf] {
+        &self.path_dirs
+    }
+
+    fn path_extensions(&self) -> &[String] {
+        &self.path_extensions
+    }
+
+    #[cfg(windows)]
+    fn long_paths(&self) -> anyhow::Result<LongPathsStatus> {
+        use windows_version::OsVersion;
+        use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+        let version = OsVersion::current();
+
+        let major_version = version.major;
+        debug!("Windows Major Version: {major_version}");
+
+        if major_version < 10 {
+            return Ok(LongPathsStatus::OldWindows);
+        }
+
+        let hlkm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        let key = hlkm.open_subkey(r"SYSTEM\CurrentControlSet\Control\FileSystem")?;
+
+        if key.get_value::<u32, _>("LongPathsEnabled")? == 0 {
+            Ok(LongPathsStatus::Disabled)
+        } else {
+            Ok(LongPathsStatus::Enabled)
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn long_paths(&self) -> anyhow::Result<LongPathsStatus> {
+        anyhow::bail!("Long paths can only be checked on Windows")
+    }
+
+    #[cfg(windows)]
+    fn developer_mode(&self) -> anyhow::Result<bool> {
+        use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+        let hlkm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        let key = hlkm.open_subkey(r"SOFTWARE\Microsoft\Windows\CurrentVersion\AppModelUnlock")?;
+
+        Ok(key.get_value::<u32, _>("AllowDevelopmentWithoutDevLicense")? == 1)
+    }
+
+    #[cfg(not(windows))]
+    fn developer_mode(&self) -> anyhow::Result<bool> {
+        anyhow::bail!("Developer mode can only be checked on Windows")
+    }
+
+    #[cfg(windows)]
+    fn filesystem(&self, path: &Path) -> anyhow::Result<String> {
+        use std::{ffi::OsString, os::windows::ffi::OsStringExt};
+        use windows::{
+            core::HSTRING,
+            Win32::{Foundation::MAX_PATH, Storage::FileSystem::GetVolumeInformationW},
+        };
+
+        let root = {
+            let mut current = path;
+
+            while let Some(parent) = current.parent() {
+                current = parent;
+            }
+
+            debug!("Checking filesystem of: {}", current.display());
+
+            current
+        };
+
+        let mut fs_name = [0u16; MAX_PATH as usize];
+
+        unsafe {
+            GetVolumeInformationW(
+                &HSTRING::from(root),
+                None,
+                None,
+                // &mut max_component_length,
+                None,
+                // &mut flags,
+                None,
+                Some(&mut fs_name),
+            )?;
+        }
+
+        let len = fs_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(fs_name.len());
+        let fs_name = OsString::from_wide(&fs_name[..len]);
+        debug!("Filesystem: {fs_name:?}");
+
+        Ok(fs_name.to_string_lossy().to_string())
+    }
+
+    #[cfg(not(windows))]
+    fn filesystem(&self, _: &Path) -> anyhow::Result<String> {
+        anyhow::bail!("Filesystems can only be checked on Windows")
+    }
+
+    fn repo_health(&self, path: &Path) -> anyhow::Result<Health> {
+        Ok(Health::inspect(path)?)
+    }
+
+    fn repo_corruption(&self, path: &Path) -> Option<String> {
+        crate::git::corruption(path)
+    }
+}
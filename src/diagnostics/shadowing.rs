@@ -0,0 +1,348 @@
+//! Checks that installed apps' shims are found when running them from the `PATH`
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use sprinkles::{
+    packages::{
+        models::manifest::{NestedArray, SingleOrArray, StringArray},
+        Manifest, MergeDefaults,
+    },
+    Architecture,
+};
+
+use super::{
+    integrity::{dirs, file_name, parse, shim_target},
+    Check, Finding, Provider, Severity,
+};
+
+/// Check that the shims directory is in the `PATH`
+pub struct ShimsInPath;
+
+impl Check for ShimsInPath {
+    fn id(&self) -> &'static str {
+        "shims-in-path"
+    }
+
+    fn title(&self) -> &'static str {
+        "The shims directory is in the PATH"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        if shims_in_path(provider) {
+            return Ok(vec![]);
+        }
+
+        let shims = &provider.paths().shims;
+
+        Ok(vec![Finding::new(format!(
+            "The shims directory {} is not in the PATH, so installed apps can't be run by name",
+            shims.display()
+        ))
+        .with_remedy(format!(
+            "Add \"{}\" to your user `PATH` environment variable",
+            shims.display()
+        ))])
+    }
+}
+
+/// Check that no other executable earlier in the `PATH` is run instead of an app's shim
+pub struct ShadowedShims;
+
+impl Check for ShadowedShims {
+    fn id(&self) -> &'static str {
+        "shadowed-shims"
+    }
+
+    fn title(&self) -> &'static str {
+        "Shims are not shadowed by other executables"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        // Without the shims directory in the `PATH`, every shim is "shadowed", which `ShimsInPath` reports
+        if !shims_in_path(provider) {
+            return Ok(vec![]);
+        }
+
+        let shims = &provider.paths().shims;
+        let mut findings = vec![];
+
+        for (app, names) in installed_shims(provider)? {
+            for name in names {
+                let Some(found) = resolve(provider, &name) else {
+                    continue;
+                };
+
+                let Some(dir) = found.parent() else {
+                    continue;
+                };
+
+                if same_dir(dir, shims) {
+                    continue;
+                }
+
+                findings.push(
+                    Finding::new(format!(
+                        "{name} from {app} runs {}, rather than Scoop's shim",
+                        found.display()
+                    ))
+                    .with_remedy(format!(
+                        "Remove or rename {}, or move \"{}\" before \"{}\" in your `PATH`",
+                        found.display(),
+                        shims.display(),
+                        dir.display()
+                    )),
+                );
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+/// Check that no two installed apps declare a shim with the same name
+pub struct DuplicateShims;
+
+impl Check for DuplicateShims {
+    fn id(&self) -> &'static str {
+        "duplicate-shims"
+    }
+
+    fn title(&self) -> &'static str {
+        "Shim names are unique"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        // Shim names are case insensitive, as they are files on Windows
+        let mut declared = BTreeMap::<String, Vec<String>>::new();
+
+        for (app, names) in installed_shims(provider)? {
+            for name in names.into_iter().unique_by(|name| name.to_lowercase()) {
+                declared
+                    .entry(name.to_lowercase())
+                    .or_default()
+                    .push(app.clone());
+            }
+        }
+
+        Ok(declared
+            .into_iter()
+            .filter(|(_, apps)| apps.len() > 1)
+            .map(|(name, apps)| {
+                let mut message = format!(
+                    "{name} is declared by {}, but only one of them can be run by name",
+                    apps.iter().join(", ")
+                );
+
+                if let Some(owner) = shim_owner(provider, &name, &apps) {
+                    message += &format!(". Currently {owner}'s {name} is used");
+                }
+
+                Finding::new(message).with_remedy(format!(
+                    "Run `scoop reset <app>` with the app whose {name} you want to use"
+                ))
+            })
+            .collect())
+    }
+}
+
+/// List the installed apps, with the names of the shims each declares
+///
+/// Apps without a readable manifest are skipped, as `BrokenApps` reports them
+fn installed_shims(provider: &dyn Provider) -> std::io::Result<Vec<(String, Vec<String>)>> {
+    Ok(dirs(provider, &provider.paths().apps)?
+        .into_iter()
+        .filter_map(|app_path| {
+            let manifest =
+                parse::<Manifest>(provider, &app_path.join("current").join("manifest.json"))?;
+
+            Some((file_name(&app_path), shim_names(manifest)))
+        })
+        .collect())
+}
+
+/// The names of the shims Scoop creates for a manifest's `bin` entries
+fn shim_names(manifest: Manifest) -> Vec<String> {
+    let Some(bin) = manifest
+        .architecture
+        .merge_default(manifest.install_config.clone(), Architecture::ARCH)
+        .bin
+    else {
+        return vec![];
+    };
+
+    match bin {
+        NestedArray::NestedArray(StringArray::Single(bin)) => vec![shim_name(&bin, None)],
+        NestedArray::NestedArray(StringArray::Array(bins)) => {
+            bins.iter().map(|bin| shim_name(bin, None)).collect()
+        }
+        NestedArray::AliasArray(bins) => bins
+            .into_iter()
+            .filter_map(|bin_alias| match bin_alias {
+                SingleOrArray::Single(bin) => Some(shim_name(&bin, None)),
+                SingleOrArray::Array(array) => {
+                    Some(shim_name(array.first()?, array.get(1).map(String::as_str)))
+                }
+            })
+            .collect(),
+    }
+}
+
+/// The name of the shim for a `bin` entry, which is its alias, or its file name without the extension
+fn shim_name(target: &str, alias: Option<&str>) -> String {
+    if let Some(alias) = alias.filter(|alias| !alias.is_empty()) {
+        return alias.to_string();
+    }
+
+    // Manifests may use either separator, regardless of the platform
+    let file_name = target.rsplit(['/', '\\']).next().unwrap_or(target);
+
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => file_name.to_string(),
+    }
+}
+
+/// Find the executable that running `name` would run, the same way [`which::which`] does
+///
+/// Each directory in the `PATH` is searched in order, trying each extension in `PATHEXT`,
+/// unless `name` already has one of those extensions
+fn resolve(provider: &dyn Provider, name: &str) -> Option<PathBuf> {
+    let extensions = provider.path_extensions();
+
+    let candidates = if extensions
+        .iter()
+        .any(|ext| name.to_lowercase().ends_with(&ext.to_lowercase()))
+    {
+        vec![name.to_string()]
+    } else {
+        extensions
+            .iter()
+            .map(|ext| format!("{name}{}", ext.to_lowercase()))
+            .collect()
+    };
+
+    provider.path_dirs().iter().find_map(|dir| {
+        candidates
+            .iter()
+            .map(|candidate| dir.join(candidate))
+            .find(|path| provider.exists(path) && !provider.is_dir(path))
+    })
+}
+
+fn shims_in_path(provider: &dyn Provider) -> bool {
+    let shims = &provider.paths().shims;
+
+    provider.path_dirs().iter().any(|dir| same_dir(dir, shims))
+}
+
+/// Find which of the `apps` the shim called `name` currently points into
+fn shim_owner<'a>(provider: &dyn Provider, name: &str, apps: &'a [String]) -> Option<&'a str> {
+    let contents = provider
+        .read_to_string(&provider.paths().shims.join(format!("{name}.shim")))
+        .ok()?;
+    let target = shim_target(&contents)?;
+
+    apps.iter()
+        .find(|app| target.starts_with(provider.paths().apps.join(app)))
+        .map(String::as_str)
+}
+
+/// Compare directories, ignoring case and trailing separators, as `PATH` entries on Windows may differ in both
+fn same_dir(a: &Path, b: &Path) -> bool {
+    fn normalise(path: &Path) -> String {
+        path.to_string_lossy()
+            .trim_end_matches(['/', '\\'])
+            .to_lowercase()
+    }
+
+    normalise(a) == normalise(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::diagnostics::fake::Fake;
+
+    use super::*;
+
+    #[test]
+    fn test_shim_name() {
+        assert_eq!(shim_name("bin\\rg.exe", None), "rg");
+        assert_eq!(shim_name("bin/tool.ps1", None), "tool");
+        assert_eq!(shim_name("bin\\python.exe", Some("python3")), "python3");
+        assert_eq!(shim_name("gradle", None), "gradle");
+    }
+
+    #[test]
+    fn test_shims_in_path() {
+        let mut fake = Fake::default();
+        assert_eq!(ShimsInPath.run(&fake).unwrap().len(), 1);
+        assert!(ShadowedShims.run(&fake).unwrap().is_empty());
+
+        let shims = fake.paths().shims.clone();
+        fake.add_path_dir(&shims);
+        assert!(ShimsInPath.run(&fake).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shadowed_shims() {
+        let mut fake = Fake::default();
+        let shims = fake.paths().shims.clone();
+
+        fake.add_path_dir("system");
+        fake.add_path_dir(&shims);
+
+        fake.install_app("git", json!({ "bin": "bin\\git.exe" }));
+        fake.install_app("python", json!({ "bin": [["python.exe", "python3"]] }));
+        fake.add_file(shims.join("git.exe"), "");
+        fake.add_file(shims.join("python3.exe"), "");
+        fake.add_file(Path::new("system").join("python3.cmd"), "");
+
+        let findings = ShadowedShims.run(&fake).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.starts_with("python3 from python"));
+    }
+
+    #[test]
+    fn test_duplicate_shims() {
+        let mut fake = Fake::default();
+        let shims = fake.paths().shims.clone();
+        let apps = fake.paths().apps.clone();
+
+        fake.install_app("git", json!({ "bin": ["bin\\git.exe", "bin\\bash.exe"] }));
+        fake.install_app("msys2", json!({ "bin": "usr\\bin\\bash.exe" }));
+        fake.add_file(
+            shims.join("bash.shim"),
+            format!(
+                "path = \"{}\"",
+                apps.join("msys2/current/usr/bin/bash.exe").display()
+            ),
+        );
+
+        let findings = DuplicateShims.run(&fake).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "bash is declared by git, msys2, but only one of them can be run by name. Currently msys2's bash is used"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Checks for system settings and programs Scoop relies on
+
+use itertools::Itertools;
+
+use super::{Check, Finding, LongPathsStatus, Provider, Severity};
+
+#[derive(Debug, Copy, Clone)]
+/// A helper program
+struct Helper {
+    /// The executable name
+    exe: &'static str,
+    /// The name of the program
+    name: &'static str,
+    /// The reason the program is needed
+    reason: &'static str,
+    /// The packages that provide the program
+    packages: &'static [&'static str],
+}
+
+const EXPECTED_HELPERS: &[Helper] = &[
+    Helper {
+        exe: "7z",
+        name: "7-Zip",
+        reason: "unpacking most programs",
+        packages: &["7zip"],
+    },
+    Helper {
+        exe: "innounp",
+        name: "Inno Setup Unpacker",
+        reason: "unpacking InnoSetup files",
+        packages: &["innounp"],
+    },
+    Helper {
+        exe: "dark",
+        name: "Dark",
+        reason: "unpacking installers created with the WiX toolkit",
+        packages: &["dark", "wixtoolset"],
+    },
+];
+
+// There is deliberately no check that Windows Defender is ignoring the Scoop directory,
+// as it's highly insecure to disable Windows Defender for Scoop
+
+/// Check that git is installed, and in the `PATH`
+pub struct Git;
+
+impl Check for Git {
+    fn id(&self) -> &'static str {
+        "git"
+    }
+
+    fn title(&self) -> &'static str {
+        "Git is installed"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        if provider.which("git").is_some() {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Finding::new(
+            "Git is not installed. Scoop relies on Git to manage itself and its buckets. sfsu does not expressly require git, but it is still recommended to install it, until sfsu can manage itself entirely without Scoop",
+        )
+        .with_remedy("Run `scoop install git` to install it")])
+    }
+}
+
+/// Check that the main bucket is added
+pub struct MainBucket;
+
+impl Check for MainBucket {
+    fn id(&self) -> &'static str {
+        "main-bucket"
+    }
+
+    fn title(&self) -> &'static str {
+        "Main bucket is installed"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        if provider.is_dir(&provider.paths().buckets.join("main")) {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Finding::new("Main bucket is not installed")
+            .with_remedy("Run `scoop bucket add main` to install it")])
+    }
+}
+
+/// Check that Windows Developer Mode is enabled
+pub struct DeveloperMode;
+
+impl Check for DeveloperMode {
+    fn id(&self) -> &'static str {
+        "developer-mode"
+    }
+
+    fn title(&self) -> &'static str {
+        "Windows Developer Mode is enabled"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        if provider.developer_mode()? {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Finding::new(
+            "Windows Developer Mode is not enabled. Operations relevant to symlinks may fail without proper rights",
+        )])
+    }
+}
+
+/// Check that long paths are enabled
+pub struct LongPaths;
+
+impl Check for LongPaths {
+    fn id(&self) -> &'static str {
+        "long-paths"
+    }
+
+    fn title(&self) -> &'static str {
+        "Long paths are enabled"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(match provider.long_paths()? {
+            LongPathsStatus::Enabled => vec![],
+            LongPathsStatus::OldWindows => vec![Finding::new(
+                "This version of Windows does not support long paths",
+            )],
+            LongPathsStatus::Disabled => vec![Finding::new("Long paths are disabled").with_remedy(
+                "Run `Set-ItemProperty 'HKLM:\\SYSTEM\\CurrentControlSet\\Control\\FileSystem' -Name 'LongPathsEnabled' -Value 1` as admin to enable it",
+            )],
+        })
+    }
+}
+
+/// Check that the Scoop directory is on an NTFS filesystem
+pub struct Ntfs;
+
+impl Check for Ntfs {
+    fn id(&self) -> &'static str {
+        "ntfs"
+    }
+
+    fn title(&self) -> &'static str {
+        "NTFS is the filesystem of the Scoop directory"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        if provider.filesystem(&provider.paths().root)? == "NTFS" {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Finding::new("NTFS is not the filesystem of the Scoop directory. Scoop requires an NTFS volume to work!")
+            .with_remedy("Please point `$env:SCOOP` or 'root_path' variable in '~/.config/scoop/config.json' to another Drive with NTFS filesystem")])
+    }
+}
+
+/// Check that the helper programs used to unpack installers are installed
+pub struct Helpers;
+
+impl Check for Helpers {
+    fn id(&self) -> &'static str {
+        "helpers"
+    }
+
+    fn title(&self) -> &'static str {
+        "Helper programs are installed"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn run(&self, provider: &dyn Provider) -> anyhow::Result<Vec<Finding>> {
+        Ok(EXPECTED_HELPERS
+            .iter()
+            .filter(|helper| provider.which(helper.exe).is_none())
+            .map(|helper| {
+                Finding::new(format!(
+                    "Missing helper: {}, used for {}",
+                    helper.name, helper.reason
+                ))
+                .with_remedy(format!(
+                    "Install it with: {}",
+                    helper
+                        .packages
+                        .iter()
+                        .map(|pkg| format!("`scoop install {pkg}`"))
+                        .join(" or ")
+                ))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::fake::Fake;
+
+    use super::*;
+
+    #[test]
+    fn test_helpers() {
+        let mut fake = Fake::default();
+        fake.add_executable("7z");
+        fake.add_executable("innounp");
+
+        let findings = Helpers.run(&fake).unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("Dark"));
+    }
+
+    #[test]
+    fn test_developer_mode() {
+        let mut fake = Fake::default();
+        assert!(DeveloperMode.run(&fake).unwrap().is_empty());
+
+        fake.set_developer_mode(false);
+        assert_eq!(DeveloperMode.run(&fake).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_long_paths() {
+        let mut fake = Fake::default();
+        assert!(LongPaths.run(&fake).unwrap().is_empty());
+
+        fake.set_long_paths(LongPathsStatus::Disabled);
+        let findings = LongPaths.run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].remedy.is_some());
+
+        fake.set_long_paths(LongPathsStatus::OldWindows);
+        let findings = LongPaths.run(&fake).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].remedy.is_none());
+    }
+
+    #[test]
+    fn test_ntfs() {
+        let mut fake = Fake::default();
+        assert!(Ntfs.run(&fake).unwrap().is_empty());
+
+        fake.set_filesystem("FAT32");
+        assert_eq!(Ntfs.run(&fake).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_main_bucket() {
+        let mut fake = Fake::default();
+        assert_eq!(MainBucket.run(&fake).unwrap().len(), 1);
+
+        fake.add_dir(fake.paths().buckets.join("main"));
+        assert!(MainBucket.run(&fake).unwrap().is_empty());
+    }
+}
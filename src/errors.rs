@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use sprinkles::git::implementations::git2;
+
 pub trait RecoverableError {
     /// Checks if the error is recoverable
     fn recoverable(&self) -> bool;
@@ -33,3 +37,123 @@ where
         }
     }
 }
+
+impl RecoverableError for std::io::Error {
+    fn recoverable(&self) -> bool {
+        use std::io::ErrorKind;
+
+        matches!(
+            self.kind(),
+            ErrorKind::TimedOut
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::Interrupted
+                | ErrorKind::UnexpectedEof
+        )
+    }
+}
+
+impl RecoverableError for git2::Error {
+    fn recoverable(&self) -> bool {
+        matches!(
+            self.class(),
+            git2::ErrorClass::Net | git2::ErrorClass::Http | git2::ErrorClass::Ssl
+        ) || matches!(self.code(), git2::ErrorCode::Eof | git2::ErrorCode::Locked)
+    }
+}
+
+impl RecoverableError for sprinkles::git::Error {
+    fn recoverable(&self) -> bool {
+        match self {
+            Self::Git2(e) => e.recoverable(),
+            _ => false,
+        }
+    }
+}
+
+impl RecoverableError for anyhow::Error {
+    fn recoverable(&self) -> bool {
+        self.chain().any(|cause| {
+            // Sprinkles wraps git errors in its own type, which may not expose the git error as its source
+            cause
+                .downcast_ref::<sprinkles::git::Error>()
+                .is_some_and(RecoverableError::recoverable)
+                || cause
+                    .downcast_ref::<git2::Error>()
+                    .is_some_and(RecoverableError::recoverable)
+                || cause
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(RecoverableError::recoverable)
+        })
+    }
+}
+
+/// Run `f`, retrying recoverable errors up to `retries` times, doubling the delay between each attempt
+///
+/// # Errors
+/// - `f` failed with an unrecoverable error
+/// - `f` still failed after all retries
+pub fn retry_with_backoff<T, E: RecoverableError>(
+    retries: u32,
+    initial_delay: Duration,
+    mut f: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut delay = initial_delay;
+
+    for _ in 0..retries {
+        match f() {
+            Err(e) if e.recoverable() => {
+                debug!("Retrying after recoverable error in {delay:?}");
+
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapped_error(class: git2::ErrorClass) -> anyhow::Error {
+        let error = git2::Error::new(git2::ErrorCode::GenericError, class, "failed to fetch");
+
+        anyhow::Error::new(sprinkles::git::Error::from(error)).context("Failed to update main")
+    }
+
+    #[test]
+    fn test_retries_wrapped_network_errors() {
+        let mut attempts = 0;
+
+        let result = retry_with_backoff(3, Duration::ZERO, || {
+            attempts += 1;
+
+            if attempts < 3 {
+                Err(wrapped_error(git2::ErrorClass::Net))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_does_not_retry_other_errors() {
+        let mut attempts = 0;
+
+        let result = retry_with_backoff(3, Duration::ZERO, || -> anyhow::Result<()> {
+            attempts += 1;
+            Err(wrapped_error(git2::ErrorClass::Reference))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,355 @@
+//! Git plumbing for inspecting, logging and rolling back Scoop and bucket repositories
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+use sprinkles::git::implementations::git2::{
+    self, BranchType, DiffOptions, ObjectType, Oid, Repository, ResetType, Sort, Status,
+    StatusOptions, Tree, TreeWalkMode, TreeWalkResult,
+};
+
+use crate::wrappers::author::Author;
+
+/// A commit, and the files it changed
+pub struct LoggedCommit {
+    pub id: Oid,
+    pub summary: String,
+    pub author: Author,
+    pub time: Option<DateTime<FixedOffset>>,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Get the commit `HEAD` points to in the repository at `path`
+///
+/// # Errors
+/// - The repository could not be opened
+/// - `HEAD` does not point to a commit
+pub fn head(path: &Path) -> Result<Oid, git2::Error> {
+    let repo = Repository::open(path)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    Ok(head)
+}
+
+/// Check if tracked files in the repository at `path` have uncommitted changes
+///
+/// # Errors
+/// - The repository could not be opened
+/// - The working tree status could not be read
+pub fn has_local_changes(path: &Path) -> Result<bool, git2::Error> {
+    local_changes(&Repository::open(path)?)
+}
+
+fn local_changes(repo: &Repository) -> Result<bool, git2::Error> {
+    let dirty = repo
+        .statuses(Some(
+            StatusOptions::new()
+                .include_untracked(false)
+                .include_ignored(false),
+        ))?
+        .iter()
+        .any(|entry| entry.status() != Status::CURRENT);
+
+    Ok(dirty)
+}
+
+/// List the commits reachable from `to` but not from `from`, newest first
+///
+/// # Errors
+/// - The repository could not be opened
+/// - The commits could not be walked or diffed
+pub fn log(path: &Path, from: Oid, to: Oid) -> Result<Vec<LoggedCommit>, git2::Error> {
+    let repo = Repository::open(path)?;
+
+    walk(&repo, from, to)
+}
+
+/// Hard reset the repository at `path` to the given revision, which may be a commit id or any other revision spec
+///
+/// Returns the commit that was reset to
+///
+/// # Errors
+/// - The repository could not be opened
+/// - The revision could not be found
+/// - The reset failed
+pub fn reset_hard(path: &Path, revision: &str) -> Result<Oid, git2::Error> {
+    let repo = Repository::open(path)?;
+    let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+
+    repo.reset(commit.as_object(), ResetType::Hard, None)?;
+
+    Ok(commit.id())
+}
+
+/// Count how many commits the current branch is behind its upstream, as of the last fetch
+///
+/// Returns [`None`] if the current branch has no upstream
+///
+/// # Errors
+/// - The repository could not be opened
+/// - `HEAD` does not point to a commit
+pub fn behind_upstream(path: &Path) -> Result<Option<usize>, git2::Error> {
+    let repo = Repository::open(path)?;
+    let head = repo.head()?;
+    let head_id = head.peel_to_commit()?.id();
+
+    let Some(upstream) = head
+        .shorthand()
+        .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+        .and_then(|branch| branch.upstream().ok())
+    else {
+        return Ok(None);
+    };
+
+    let upstream_id = upstream.get().peel_to_commit()?.id();
+    let (_, behind) = repo.graph_ahead_behind(head_id, upstream_id)?;
+
+    Ok(Some(behind))
+}
+
+#[derive(Debug, Clone)]
+/// The state of a repository's branches and working tree
+pub struct Health {
+    /// The checked out branch, or [`None`] if `HEAD` is detached
+    pub branch: Option<String>,
+    /// The branch `origin/HEAD` points to, if it is set
+    pub default_branch: Option<String>,
+    /// If tracked files have uncommitted changes
+    pub dirty: bool,
+    /// The url of the `origin` remote
+    pub origin: Option<String>,
+}
+
+impl Health {
+    /// Inspect the repository at `path`
+    ///
+    /// # Errors
+    /// - The repository could not be opened
+    /// - The working tree status could not be read
+    pub fn inspect(path: &Path) -> Result<Self, git2::Error> {
+        let repo = Repository::open(path)?;
+
+        let branch = if repo.head_detached()? {
+            None
+        } else {
+            repo.head()?.shorthand().map(ToString::to_string)
+        };
+
+        let default_branch = repo
+            .find_reference("refs/remotes/origin/HEAD")
+            .ok()
+            .and_then(|reference| {
+                reference
+                    .symbolic_target()?
+                    .strip_prefix("refs/remotes/origin/")
+                    .map(ToString::to_string)
+            });
+
+        let dirty = local_changes(&repo)?;
+
+        let origin = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(ToString::to_string));
+
+        Ok(Self {
+            branch,
+            default_branch,
+            dirty,
+            origin,
+        })
+    }
+}
+
+/// Check that every object in the checked out commit's tree of the repository at `path` can be found
+///
+/// This doesn't read every object in the database like `git fsck`, which would be far too slow for large buckets
+///
+/// Returns why the objects could not be read, or [`None`] if they could
+pub fn corruption(path: &Path) -> Option<String> {
+    let repo = match Repository::open(path) {
+        Ok(repo) => repo,
+        Err(e) => return Some(e.message().to_string()),
+    };
+
+    let (odb, tree) = match repo
+        .odb()
+        .and_then(|odb| Ok((odb, repo.head()?.peel_to_tree()?)))
+    {
+        Ok(found) => found,
+        Err(e) => return Some(e.message().to_string()),
+    };
+
+    let mut missing = None;
+
+    let walked = tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        // Submodules point to commits in other repositories
+        if entry.kind() == Some(ObjectType::Commit) || odb.exists(entry.id()) {
+            return TreeWalkResult::Ok;
+        }
+
+        missing = Some(format!(
+            "{root}{} ({}) is missing",
+            entry.name().unwrap_or_default(),
+            entry.id()
+        ));
+        TreeWalkResult::Abort
+    });
+
+    match walked {
+        Ok(()) => missing,
+        Err(e) => Some(missing.unwrap_or_else(|| e.message().to_string())),
+    }
+}
+
+fn walk(repo: &Repository, from: Oid, to: Oid) -> Result<Vec<LoggedCommit>, git2::Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(to)?;
+    revwalk.hide(from)?;
+
+    revwalk
+        .map(|id| {
+            let commit = repo.find_commit(id?)?;
+
+            let parent_tree = match commit.parents().next() {
+                Some(parent) => Some(parent.tree()?),
+                None => None,
+            };
+            let paths = changed_paths(repo, parent_tree.as_ref(), &commit.tree()?)?;
+
+            let time = {
+                let time = commit.time();
+
+                FixedOffset::east_opt(time.offset_minutes() * 60).and_then(|offset| {
+                    DateTime::from_timestamp(time.seconds(), 0)
+                        .map(|time| time.with_timezone(&offset))
+                })
+            };
+
+            Ok(LoggedCommit {
+                id: commit.id(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author: Author::from(commit.author()),
+                time,
+                paths,
+            })
+        })
+        .collect()
+}
+
+fn changed_paths(
+    repo: &Repository,
+    old_tree: Option<&Tree<'_>>,
+    new_tree: &Tree<'_>,
+) -> Result<Vec<PathBuf>, git2::Error> {
+    let diff = repo.diff_tree_to_tree(old_tree, Some(new_tree), Some(&mut DiffOptions::new()))?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().map(Path::to_path_buf))
+        .collect())
+}
+
+/// Changes fetched from a repository's upstream, which have not been merged
+pub struct Incoming {
+    repo: Repository,
+    head: Oid,
+    upstream: Oid,
+}
+
+impl Incoming {
+    /// Fetch the upstream of the repository's current branch, without moving `HEAD` or touching the working tree
+    ///
+    /// Returns [`None`] if there are no incoming changes
+    ///
+    /// # Errors
+    /// - The repository could not be opened
+    /// - The current branch has no upstream
+    /// - The fetch failed
+    pub fn fetch(path: &Path) -> Result<Option<Self>, git2::Error> {
+        let repo = Repository::open(path)?;
+
+        let (head, upstream_ref) = {
+            let head = repo.head()?;
+            let branch_name = head
+                .shorthand()
+                .ok_or_else(|| git2::Error::from_str("HEAD is not a valid branch name"))?;
+            let branch = repo.find_branch(branch_name, BranchType::Local)?;
+            let upstream = branch.upstream()?;
+            let upstream_ref = upstream
+                .get()
+                .name()
+                .ok_or_else(|| git2::Error::from_str("Upstream is not a valid reference name"))?
+                .to_string();
+
+            (head.peel_to_commit()?.id(), upstream_ref)
+        };
+
+        {
+            let remote_name = repo.branch_remote_name(&upstream_ref)?;
+            let remote_name = remote_name
+                .as_str()
+                .ok_or_else(|| git2::Error::from_str("Remote name is not valid utf8"))?;
+
+            // Fetching with the configured refspecs only updates remote tracking branches
+            repo.find_remote(remote_name)?
+                .fetch(&[] as &[&str], None, None)?;
+        }
+
+        let upstream = repo.find_reference(&upstream_ref)?.peel_to_commit()?.id();
+
+        if repo.graph_descendant_of(head, upstream)? || head == upstream {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            repo,
+            head,
+            upstream,
+        }))
+    }
+
+    /// List the incoming commits, newest first
+    ///
+    /// # Errors
+    /// - The commits could not be walked
+    pub fn commits(&self) -> Result<Vec<LoggedCommit>, git2::Error> {
+        walk(&self.repo, self.head, self.upstream)
+    }
+
+    /// List the paths of files changed by the incoming commits
+    ///
+    /// # Errors
+    /// - The trees could not be diffed
+    pub fn changed_paths(&self) -> Result<Vec<PathBuf>, git2::Error> {
+        let base = self.repo.merge_base(self.head, self.upstream)?;
+
+        let old_tree = self.repo.find_commit(base)?.tree()?;
+        let new_tree = self.repo.find_commit(self.upstream)?.tree()?;
+
+        changed_paths(&self.repo, Some(&old_tree), &new_tree)
+    }
+
+    /// Read a file at the current `HEAD`
+    ///
+    /// Returns [`None`] if the file does not exist
+    pub fn read_current(&self, path: &Path) -> Option<Vec<u8>> {
+        self.read_at(self.head, path)
+    }
+
+    /// Read a file at the fetched upstream
+    ///
+    /// Returns [`None`] if the file does not exist
+    pub fn read_incoming(&self, path: &Path) -> Option<Vec<u8>> {
+        self.read_at(self.upstream, path)
+    }
+
+    fn read_at(&self, commit: Oid, path: &Path) -> Option<Vec<u8>> {
+        let tree = self.repo.find_commit(commit).ok()?.tree().ok()?;
+        let entry = tree.get_path(path).ok()?;
+        let blob = self.repo.find_blob(entry.id()).ok()?;
+
+        Some(blob.content().to_vec())
+    }
+}
@@ -0,0 +1,107 @@
+//! A journal of the commits Scoop and each bucket were at before being updated
+//!
+//! `sfsu update` records each repository's `HEAD` before pulling, so that `sfsu bucket rollback`
+//! can return a bucket to where it was when a bad manifest lands upstream.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sprinkles::contexts::ScoopContext;
+
+/// The name Scoop itself is recorded under in the journal
+pub const SCOOP: &str = "Scoop";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The commits repositories were at before an update
+pub struct Update {
+    /// When the update happened
+    pub time: DateTime<Local>,
+    /// The commit each updated repository was at before the update, keyed by name
+    pub commits: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// Previous updates, oldest first
+pub struct Journal {
+    updates: Vec<Update>,
+}
+
+impl Journal {
+    /// The maximum number of updates to keep in the journal
+    const MAX_UPDATES: usize = 20;
+
+    /// The path to the journal, in the Scoop directory
+    pub fn path(ctx: &impl ScoopContext) -> PathBuf {
+        ctx.path().join("sfsu").join("update-journal.json")
+    }
+
+    /// Load the journal, or an empty journal if it doesn't exist
+    ///
+    /// # Errors
+    /// - The journal exists but could not be read or parsed
+    pub fn load(ctx: &impl ScoopContext) -> anyhow::Result<Self> {
+        let path = Self::path(ctx);
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file = File::open(path)?;
+
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Save the journal
+    ///
+    /// # Errors
+    /// - The journal could not be written
+    pub fn save(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let path = Self::path(ctx);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    /// Record an update, dropping the oldest updates if the journal is full
+    pub fn record(&mut self, commits: BTreeMap<String, String>) {
+        if commits.is_empty() {
+            return;
+        }
+
+        self.updates.push(Update {
+            time: Local::now(),
+            commits,
+        });
+
+        let excess = self.updates.len().saturating_sub(Self::MAX_UPDATES);
+        self.updates.drain(..excess);
+    }
+
+    #[must_use]
+    /// Get the most recent update
+    pub fn last(&self) -> Option<&Update> {
+        self.updates.last()
+    }
+
+    #[must_use]
+    /// Get the commit the named repository was at before it was last updated
+    pub fn last_commit(&self, name: &str) -> Option<&str> {
+        self.updates
+            .iter()
+            .rev()
+            .find_map(|update| update.commits.get(name))
+            .map(String::as_str)
+    }
+}
@@ -5,17 +5,21 @@
     rust_2024_compatibility
 )]
 
-// TODO: Replace regex with glob
-
 mod calm_panic;
 mod commands;
+mod completions;
+mod config;
 mod diagnostics;
 mod errors;
+mod git;
+mod journal;
 mod limits;
 mod logging;
 mod models;
 mod output;
+mod pattern;
 mod progress;
+mod shell;
 mod validations;
 mod wrappers;
 
@@ -4,6 +4,8 @@ use serde::Serialize;
 
 use sprinkles::packages::Manifest;
 
+use crate::{models::status::Alternative, wrappers::version::Change};
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 /// The outdated info
 pub struct Info {
@@ -13,22 +15,44 @@ pub struct Info {
     pub current: String,
     /// The available version
     pub available: String,
+    /// How the version would change when updating, or [`None`] if it is up to date
+    pub change: Option<Change>,
+    /// Another bucket with a newer version than the app's bucket
+    pub alternative_bucket: Option<String>,
+    /// The version available in the alternative bucket
+    pub alternative_version: Option<String>,
 }
 
 impl Info {
     #[must_use]
-    /// Get the outdated info from a local and remote manifest combo
+    /// Get the outdated info from a local and remote manifest combo, and an optional alternative bucket
     ///
-    /// Returns [`None`] if they have the same version
-    pub fn from_manifests(local: &Manifest, remote: &Manifest) -> Option<Self> {
-        if local.version == remote.version {
-            None
-        } else {
-            Some(Info {
-                name: unsafe { remote.name() }.to_string(),
-                current: local.version.to_string(),
-                available: remote.version.to_string(),
-            })
+    /// Returns [`None`] if they have the same version, and there is no alternative
+    pub fn from_manifests(
+        local: &Manifest,
+        remote: &Manifest,
+        alternative: Option<Alternative>,
+    ) -> Option<Self> {
+        let current = local.version.to_string();
+        let available = remote.version.to_string();
+
+        let change = Change::between(&current, &available);
+
+        if change.is_none() && alternative.is_none() {
+            return None;
         }
+
+        let (alternative_bucket, alternative_version) = alternative
+            .map(|alternative| (alternative.bucket, alternative.version))
+            .unzip();
+
+        Some(Info {
+            name: unsafe { remote.name() }.to_string(),
+            current,
+            available,
+            change,
+            alternative_bucket,
+            alternative_version,
+        })
     }
 }
@@ -1,5 +1,7 @@
 //! Status information
 
+use std::cmp::Ordering;
+
 use itertools::Itertools as _;
 use quork::traits::truthy::ContainsTruth;
 use serde::Serialize;
@@ -13,6 +15,70 @@ use sprinkles::{
     },
 };
 
+use crate::wrappers::version::{Change, Version};
+
+const INSTALL_FAILED: &str = "Install failed";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A bucket other than the one an app was installed from, which has a newer version of the app
+pub struct Alternative {
+    /// The name of the bucket
+    pub bucket: String,
+    /// The version the bucket has
+    pub version: String,
+}
+
+impl Alternative {
+    #[must_use]
+    /// Find the bucket with the newest version of the app, if it is newer than both the `current`
+    /// installed version and the `available` version from the bucket the app was installed from
+    pub fn find(
+        buckets: &[Bucket],
+        name: &str,
+        installed_bucket: &str,
+        current: &str,
+        available: &str,
+    ) -> Option<Self> {
+        Self::newest(
+            buckets
+                .iter()
+                .filter(|bucket| bucket.name() != installed_bucket)
+                .filter_map(|bucket| {
+                    let manifest = bucket.get_manifest(name).ok()?;
+
+                    Some(Self {
+                        bucket: bucket.name().to_string(),
+                        version: manifest.version.to_string(),
+                    })
+                }),
+            current,
+            available,
+        )
+    }
+
+    /// Pick the newest alternative that is an upgrade over both the `current` and `available` versions
+    ///
+    /// Comparing against both means an app that is ahead of its bucket, i.e after the bucket was rolled back,
+    /// isn't "upgraded" to a version that is already installed, or older
+    fn newest(
+        alternatives: impl IntoIterator<Item = Self>,
+        current: &str,
+        available: &str,
+    ) -> Option<Self> {
+        alternatives
+            .into_iter()
+            .filter(|alternative| {
+                Change::between(current, &alternative.version) == Some(Change::Upgrade)
+                    && Change::between(available, &alternative.version) == Some(Change::Upgrade)
+            })
+            .max_by(|a, b| {
+                Version::new(&a.version)
+                    .partial_cmp(&Version::new(&b.version))
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 #[allow(clippy::struct_field_names)]
 /// The status info
@@ -23,6 +89,12 @@ pub struct Info {
     pub current: String,
     /// The available version
     pub available: String,
+    /// How the version would change when updating, or [`None`] if it is up to date
+    pub change: Option<Change>,
+    /// Another bucket with a newer version than the app's bucket
+    pub alternative_bucket: Option<String>,
+    /// The version available in the alternative bucket
+    pub alternative_version: Option<String>,
     /// The missing dependencies
     pub missing_dependencies: Vec<package::Reference>,
     /// Additional information
@@ -77,18 +149,97 @@ impl Info {
         let mut info = String::new();
 
         if failed {
-            info += "Install failed";
+            info += INSTALL_FAILED;
         }
         if held {
             info += "Held package";
         }
 
+        let current = local_manifest.version.to_string();
+        let available = remote_manifest.version.to_string();
+
         Ok(Info {
             name: unsafe { remote_manifest.name() }.to_string(),
-            current: local_manifest.version.to_string(),
-            available: remote_manifest.version.to_string(),
+            change: Change::between(&current, &available),
+            current,
+            available,
+            alternative_bucket: None,
+            alternative_version: None,
             missing_dependencies,
             info: (!info.is_empty()).then_some(info),
         })
     }
+
+    #[must_use]
+    /// Check if the app failed to install
+    pub fn install_failed(&self) -> bool {
+        self.info
+            .as_ref()
+            .is_some_and(|info| info.contains(INSTALL_FAILED))
+    }
+
+    #[must_use]
+    /// Check the given buckets for a newer version than the one in the bucket the app was installed from
+    pub fn with_alternative(mut self, buckets: &[Bucket], installed_bucket: &str) -> Self {
+        if let Some(alternative) = Alternative::find(
+            buckets,
+            &self.name,
+            installed_bucket,
+            &self.current,
+            &self.available,
+        ) {
+            self.alternative_bucket = Some(alternative.bucket);
+            self.alternative_version = Some(alternative.version);
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alternative(bucket: &str, version: &str) -> Alternative {
+        Alternative {
+            bucket: bucket.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_newest_alternative() {
+        let alternatives = [
+            alternative("extras", "1.1.0"),
+            alternative("versions", "1.2.0"),
+        ];
+
+        assert_eq!(
+            Alternative::newest(alternatives.clone(), "1.0.0", "1.0.0"),
+            Some(alternative("versions", "1.2.0"))
+        );
+        assert_eq!(Alternative::newest(alternatives, "1.2.0", "1.2.0"), None);
+    }
+
+    #[test]
+    fn test_newest_alternative_when_current_is_ahead() {
+        // The installed bucket was rolled back to 1.0.0, after 1.3.0 was installed from it
+        let alternatives = [
+            alternative("extras", "1.1.0"),
+            alternative("versions", "1.3.0"),
+        ];
+
+        assert_eq!(Alternative::newest(alternatives, "1.3.0", "1.0.0"), None);
+        assert_eq!(
+            Alternative::newest(
+                [
+                    alternative("extras", "1.1.0"),
+                    alternative("versions", "1.4.0")
+                ],
+                "1.3.0",
+                "1.0.0"
+            ),
+            Some(alternative("versions", "1.4.0"))
+        );
+    }
 }
@@ -0,0 +1,274 @@
+//! Shared pattern matching
+//!
+//! Patterns can be written in glob, regex or literal syntax, and are all compiled down to a [`Regex`].
+
+use clap::Parser;
+use regex::Regex;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// The syntax a pattern is written in
+pub enum Syntax {
+    /// Glob syntax (i.e `python*`). Always matches the whole haystack
+    Glob,
+    /// Rust regex syntax
+    Regex,
+    /// Match the pattern literally
+    Literal,
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Flags to choose the syntax of a command's patterns
+pub struct SyntaxArgs {
+    #[clap(
+        long,
+        global = true,
+        help = "Treat patterns as globs",
+        conflicts_with_all = ["regex", "literal"]
+    )]
+    pub glob: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Treat patterns as regexes, using Rust Regex syntax",
+        conflicts_with = "literal"
+    )]
+    pub regex: bool,
+
+    #[clap(long, global = true, help = "Treat patterns as literal text")]
+    pub literal: bool,
+}
+
+impl SyntaxArgs {
+    #[must_use]
+    /// Get the chosen syntax, or the command's default if none was chosen
+    pub fn syntax_or(&self, default: Syntax) -> Syntax {
+        if self.glob {
+            Syntax::Glob
+        } else if self.regex {
+            Syntax::Regex
+        } else if self.literal {
+            Syntax::Literal
+        } else {
+            default
+        }
+    }
+
+    #[must_use]
+    /// Check if a syntax was explicitly chosen
+    pub fn is_explicit(&self) -> bool {
+        self.glob || self.regex || self.literal
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+/// The [`SyntaxArgs`] flags, for the subcommands of a command that flattens [`SyntaxArgs`]
+pub struct GlobalSyntaxArgs {
+    #[clap(from_global)]
+    pub glob: bool,
+
+    #[clap(from_global)]
+    pub regex: bool,
+
+    #[clap(from_global)]
+    pub literal: bool,
+}
+
+impl From<&GlobalSyntaxArgs> for SyntaxArgs {
+    fn from(args: &GlobalSyntaxArgs) -> Self {
+        Self {
+            glob: args.glob,
+            regex: args.regex,
+            literal: args.literal,
+        }
+    }
+}
+
+impl From<&SyntaxArgs> for GlobalSyntaxArgs {
+    fn from(args: &SyntaxArgs) -> Self {
+        Self {
+            glob: args.glob,
+            regex: args.regex,
+            literal: args.literal,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A compiled pattern
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compile a pattern that may match anywhere in the haystack
+    ///
+    /// Glob patterns still have to match the whole haystack
+    ///
+    /// # Errors
+    /// - The pattern is not a valid regex
+    pub fn new(pattern: &str, syntax: Syntax, case_sensitive: bool) -> Result<Self, regex::Error> {
+        let source = match syntax {
+            Syntax::Glob => glob_to_regex(pattern),
+            Syntax::Regex => pattern.to_string(),
+            Syntax::Literal => regex::escape(pattern),
+        };
+
+        Self::compile(&source, case_sensitive)
+    }
+
+    /// Compile a pattern that must match the whole haystack
+    ///
+    /// # Errors
+    /// - The pattern is not a valid regex
+    pub fn whole(
+        pattern: &str,
+        syntax: Syntax,
+        case_sensitive: bool,
+    ) -> Result<Self, regex::Error> {
+        let source = match syntax {
+            Syntax::Glob => glob_to_regex(pattern),
+            Syntax::Regex => format!("^(?:{pattern})$"),
+            Syntax::Literal => format!("^{}$", regex::escape(pattern)),
+        };
+
+        Self::compile(&source, case_sensitive)
+    }
+
+    fn compile(source: &str, case_sensitive: bool) -> Result<Self, regex::Error> {
+        let flags = if case_sensitive { "" } else { "(?i)" };
+
+        Regex::new(&format!("{flags}{source}")).map(Self)
+    }
+
+    #[must_use]
+    /// Check if the pattern matches the haystack
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.0.is_match(haystack)
+    }
+
+    #[must_use]
+    /// Find the first match of the pattern in the haystack
+    pub fn find<'h>(&self, haystack: &'h str) -> Option<regex::Match<'h>> {
+        self.0.find(haystack)
+    }
+
+    #[must_use]
+    /// Get the compiled regex
+    pub fn as_regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+/// Translate a glob into an anchored regex
+///
+/// Supports `*`, `?`, `[...]` (and `[!...]`) classes, `{a,b}` alternatives and `\` escapes
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut alternatives = 0usize;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            '[' => {
+                let class = chars.clone().collect::<String>();
+
+                match class.char_indices().skip(1).find(|(_, c)| *c == ']') {
+                    Some((end, _)) => {
+                        let mut inner = &class[..end];
+                        regex.push('[');
+
+                        if let Some(negated) = inner.strip_prefix('!') {
+                            regex.push('^');
+                            inner = negated;
+                        }
+
+                        for c in inner.chars() {
+                            if matches!(c, '\\' | '[' | ']' | '&' | '~' | '^') {
+                                regex.push('\\');
+                            }
+                            regex.push(c);
+                        }
+
+                        regex.push(']');
+
+                        // Skip the class and its closing bracket
+                        for _ in 0..=class[..end].chars().count() {
+                            chars.next();
+                        }
+                    }
+                    None => regex.push_str(r"\["),
+                }
+            }
+            '{' => {
+                alternatives += 1;
+                regex.push_str("(?:");
+            }
+            ',' if alternatives > 0 => regex.push('|'),
+            '}' if alternatives > 0 => {
+                alternatives -= 1;
+                regex.push(')');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    // Close any unterminated alternatives, so that the regex is still valid
+    for _ in 0..alternatives {
+        regex.push(')');
+    }
+
+    regex.push('$');
+
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() {
+        let pattern = Pattern::new("python*", Syntax::Glob, false).unwrap();
+        assert!(pattern.is_match("python"));
+        assert!(pattern.is_match("Python311"));
+        assert!(!pattern.is_match("cpython"));
+
+        let pattern = Pattern::new("7z?p", Syntax::Glob, false).unwrap();
+        assert!(pattern.is_match("7zip"));
+        assert!(!pattern.is_match("7zipp"));
+
+        let pattern = Pattern::new("[!a-c]*.{json,toml}", Syntax::Glob, false).unwrap();
+        assert!(pattern.is_match("scoop.json"));
+        assert!(pattern.is_match("sfsu.toml"));
+        assert!(!pattern.is_match("bucket.json"));
+        assert!(!pattern.is_match("scoop.yaml"));
+
+        let pattern = Pattern::new("node.js", Syntax::Glob, false).unwrap();
+        assert!(pattern.is_match("node.js"));
+        assert!(!pattern.is_match("nodexjs"));
+    }
+
+    #[test]
+    fn test_regex_and_literal() {
+        let pattern = Pattern::new("^go", Syntax::Regex, false).unwrap();
+        assert!(pattern.is_match("gops"));
+        assert!(!pattern.is_match("mongo"));
+
+        let pattern = Pattern::whole("go", Syntax::Regex, false).unwrap();
+        assert!(!pattern.is_match("gops"));
+
+        let pattern = Pattern::new("c++", Syntax::Literal, true).unwrap();
+        assert!(pattern.is_match("notepad-c++"));
+        assert!(!pattern.is_match("notepad-C++"));
+
+        let pattern = Pattern::whole("c++", Syntax::Literal, false).unwrap();
+        assert!(pattern.is_match("C++"));
+        assert!(!pattern.is_match("notepad-c++"));
+    }
+}
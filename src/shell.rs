@@ -0,0 +1,95 @@
+//! Shells that sfsu can generate hooks and completions for
+
+use std::{fmt::Display, path::PathBuf, process::Command};
+
+use anyhow::Context;
+
+use clap::ValueEnum;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// A supported shell
+pub enum Shell {
+    Powershell,
+    Bash,
+    Zsh,
+    Nu,
+    Fish,
+    Elvish,
+}
+
+impl Shell {
+    #[must_use]
+    /// The name of the shell's config file, as referred to in install instructions
+    pub const fn config(self) -> &'static str {
+        match self {
+            Shell::Powershell => "$PROFILE",
+            Shell::Bash => "bashrc",
+            Shell::Zsh => "zshrc",
+            Shell::Nu => "config.nu",
+            Shell::Fish => "~/.config/fish/config.fish",
+            Shell::Elvish => "~/.config/elvish/rc.elv",
+        }
+    }
+
+    /// Find the path to the shell's config file
+    ///
+    /// `PowerShell` and Nushell are asked for their config path, as it depends on the version and platform
+    ///
+    /// # Errors
+    /// - The shell could not be run to find its config path
+    /// - The home directory could not be found
+    pub fn config_path(self) -> anyhow::Result<PathBuf> {
+        match self {
+            Shell::Powershell => {
+                // Prefer PowerShell 7+, falling back to Windows PowerShell
+                let executable = if which::which("pwsh").is_ok() {
+                    "pwsh"
+                } else {
+                    "powershell"
+                };
+
+                ask(
+                    executable,
+                    &["-NoLogo", "-NoProfile", "-Command", "$PROFILE"],
+                )
+            }
+            Shell::Nu => ask("nu", &["--commands", "$nu.config-path"]),
+            Shell::Bash => Ok(home()?.join(".bashrc")),
+            Shell::Zsh => Ok(home()?.join(".zshrc")),
+            Shell::Fish => Ok(home()?.join(".config").join("fish").join("config.fish")),
+            Shell::Elvish => Ok(home()?.join(".config").join("elvish").join("rc.elv")),
+        }
+    }
+}
+
+fn home() -> anyhow::Result<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .context("Could not find your home directory")
+}
+
+/// Run the shell, and read the path it prints
+fn ask(executable: &str, args: &[&str]) -> anyhow::Result<PathBuf> {
+    let output = Command::new(executable)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {executable} to find its config file"))?;
+
+    let path = String::from_utf8(output.stdout)?.trim().to_string();
+
+    if !output.status.success() || path.is_empty() {
+        anyhow::bail!("{executable} did not report its config file");
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_possible_value()
+            .expect("no skipped variants")
+            .get_name()
+            .fmt(f)
+    }
+}
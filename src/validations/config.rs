@@ -8,3 +8,79 @@ impl super::Validate for sprinkles::config::Scoop {
         Ok(())
     }
 }
+
+impl super::Validate for crate::config::Config {
+    fn validate(&self) -> anyhow::Result<()> {
+        use clap::CommandFactory;
+
+        let command = crate::Args::command();
+
+        // Hooks are embedded in shell scripts, so only allow characters that don't need quoting.
+        // `#` starts a comment in every supported shell, and `@` and `,` are operators in PowerShell
+        let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=".contains(c);
+
+        for (name, hook) in &self.hooks {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                anyhow::bail!(
+                    "Invalid hook name \"{name}\". Hook names can only contain letters, numbers and dashes"
+                );
+            }
+
+            let mut args = hook.split_whitespace();
+
+            let Some(subcommand) = args.next() else {
+                anyhow::bail!("Hook \"{name}\" has no command");
+            };
+
+            if !command
+                .find_subcommand(subcommand)
+                .is_some_and(|subcommand| !subcommand.is_hide_set())
+            {
+                anyhow::bail!(
+                    "Hook \"{name}\" runs \"{subcommand}\", which is not an sfsu command"
+                );
+            }
+
+            if let Some(arg) = args.find(|arg| !arg.chars().all(is_safe)) {
+                anyhow::bail!(
+                    "Hook \"{name}\" has an invalid argument \"{arg}\". Arguments cannot contain quotes, spaces or shell syntax"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::Config, validations::Validate};
+
+    fn config(name: &str, hook: &str) -> Config {
+        Config {
+            hooks: [(name.to_string(), hook.to_string())].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_hook() {
+        assert!(config("up", "update --changelog").validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_hooks() {
+        assert!(config("up", "not-a-command").validate().is_err());
+        assert!(config("up", "").validate().is_err());
+        assert!(config("up date", "update").validate().is_err());
+        assert!(config("up", "update $(rm -rf ~)").validate().is_err());
+    }
+
+    #[test]
+    fn test_shell_syntax_arguments() {
+        assert!(config("s", "search --bucket=main").validate().is_ok());
+        assert!(config("s", "search #comment").validate().is_err());
+        assert!(config("s", "search @args").validate().is_err());
+        assert!(config("s", "search a,b").validate().is_err());
+    }
+}
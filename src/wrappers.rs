@@ -2,7 +2,9 @@
 
 pub mod author;
 pub mod bool;
+pub mod duration;
 pub mod header;
 pub mod serialize;
 pub mod sizes;
 pub mod time;
+pub mod version;
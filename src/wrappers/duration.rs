@@ -0,0 +1,159 @@
+//! A wrapper for human readable durations, i.e `6h` or `1d12h`
+
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use derive_more::{Deref, DerefMut};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Empty,
+    MissingUnit(u64),
+    InvalidUnit(char),
+    MissingNumber(char),
+    Overflow,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Duration is empty"),
+            Self::MissingUnit(number) => {
+                write!(
+                    f,
+                    "Missing unit after {number}. Expected one of s, m, h, d or w"
+                )
+            }
+            Self::InvalidUnit(unit) => {
+                write!(f, "Invalid unit {unit}. Expected one of s, m, h, d or w")
+            }
+            Self::MissingNumber(unit) => write!(f, "Missing number before unit {unit}"),
+            Self::Overflow => write!(f, "Duration is too long"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut)]
+/// A duration, parsed from and displayed as a number of weeks, days, hours, minutes and seconds
+pub struct NicerDuration(Duration);
+
+impl NicerDuration {
+    const UNITS: [(char, u64); 5] = [
+        ('w', 7 * 24 * 60 * 60),
+        ('d', 24 * 60 * 60),
+        ('h', 60 * 60),
+        ('m', 60),
+        ('s', 1),
+    ];
+}
+
+impl From<Duration> for NicerDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl FromStr for NicerDuration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let mut secs: u64 = 0;
+        let mut number: Option<u64> = None;
+
+        for c in s.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                number = Some(
+                    number
+                        .unwrap_or_default()
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(u64::from(digit)))
+                        .ok_or(Error::Overflow)?,
+                );
+                continue;
+            }
+
+            let (_, unit_secs) = Self::UNITS
+                .iter()
+                .find(|(unit, _)| *unit == c.to_ascii_lowercase())
+                .ok_or(Error::InvalidUnit(c))?;
+            let value = number.take().ok_or(Error::MissingNumber(c))?;
+
+            secs = value
+                .checked_mul(*unit_secs)
+                .and_then(|value| secs.checked_add(value))
+                .ok_or(Error::Overflow)?;
+        }
+
+        if let Some(number) = number {
+            return Err(Error::MissingUnit(number));
+        }
+
+        Ok(Self(Duration::from_secs(secs)))
+    }
+}
+
+impl Display for NicerDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut secs = self.0.as_secs();
+
+        if secs == 0 {
+            return write!(f, "0s");
+        }
+
+        for (unit, unit_secs) in Self::UNITS {
+            let count = secs / unit_secs;
+
+            if count > 0 {
+                write!(f, "{count}{unit}")?;
+                secs %= unit_secs;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<u64, Error> {
+        s.parse::<NicerDuration>().map(|d| d.as_secs())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("6h"), Ok(6 * 60 * 60));
+        assert_eq!(parse("1d"), Ok(24 * 60 * 60));
+        assert_eq!(parse("1d12h"), Ok(36 * 60 * 60));
+        assert_eq!(parse("90s"), Ok(90));
+        assert_eq!(parse("2W"), Ok(14 * 24 * 60 * 60));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("6"), Err(Error::MissingUnit(6)));
+        assert_eq!(parse("6y"), Err(Error::InvalidUnit('y')));
+        assert_eq!(parse("h"), Err(Error::MissingNumber('h')));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            NicerDuration::from(Duration::from_secs(90)).to_string(),
+            "1m30s"
+        );
+        assert_eq!(
+            "1d12h".parse::<NicerDuration>().unwrap().to_string(),
+            "1d12h"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! A wrapper for Scoop version strings, ordered the way Scoop orders them.
+//!
+//! Scoop versions are not semver. They may be dates (`2024-01-15`), have any number of parts (`2.43.0.windows.1`),
+//! pre-release labels (`1.2.3-beta.4`), build suffixes (`1.2.3+20240115`) or be entirely unorderable (`nightly`).
+
+use std::{cmp::Ordering, fmt::Display};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+/// How an app's version would change when updating
+pub enum Change {
+    /// The available version is newer
+    Upgrade,
+    /// The available version is older, i.e the bucket was rolled back
+    Downgrade,
+    /// The versions can't be ordered
+    Unknown,
+}
+
+impl Change {
+    #[must_use]
+    /// Get the change from the `current` version to the `available` version
+    ///
+    /// Returns [`None`] if the versions are the same
+    pub fn between(current: &str, available: &str) -> Option<Self> {
+        match Version::new(available).partial_cmp(&Version::new(current)) {
+            Some(Ordering::Greater) => Some(Self::Upgrade),
+            Some(Ordering::Less) => Some(Self::Downgrade),
+            Some(Ordering::Equal) => None,
+            None => Some(Self::Unknown),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Upgrade => write!(f, "upgrade"),
+            Self::Downgrade => write!(f, "downgrade"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(u64),
+    Text(String),
+}
+
+impl PartialOrd for Token {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Token {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Token::Number(a), Token::Number(b)) => a.cmp(b),
+            // Pre-release labels sort alphabetically, which happens to order alpha < beta < pre < rc
+            (Token::Text(a), Token::Text(b)) => a.cmp(b),
+            (Token::Number(_), Token::Text(_)) => Ordering::Greater,
+            (Token::Text(_), Token::Number(_)) => Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A Scoop version string
+pub struct Version {
+    raw: String,
+    release: Vec<Token>,
+    pre_release: Option<Vec<Token>>,
+    build: Vec<Token>,
+}
+
+impl Version {
+    #[must_use]
+    /// Parse a version string
+    pub fn new(version: &str) -> Self {
+        let raw = version.trim().to_lowercase();
+
+        let (rest, build) = raw.split_once('+').unwrap_or((&raw, ""));
+
+        // A dash only starts a pre-release label if it is followed by text,
+        // as dashes are also used to separate the parts of date versions
+        let pre_release_start = rest
+            .char_indices()
+            .zip(rest.chars().skip(1))
+            .find(|((_, c), next)| *c == '-' && next.is_alphabetic())
+            .map(|((i, _), _)| i + 1)
+            .or_else(|| trailing_label_start(rest));
+
+        let (release, pre_release) = match pre_release_start {
+            Some(i) => (&rest[..i], Some(pre_release_tokens(&rest[i..]))),
+            None => (rest, None),
+        };
+
+        Self {
+            release: tokenize(release),
+            pre_release,
+            build: tokenize(build),
+            raw,
+        }
+    }
+
+    /// Check if the version can't be ordered against other versions
+    fn is_unordered(&self) -> bool {
+        matches!(self.raw.as_str(), "latest" | "nightly" | "dev") || self.raw.starts_with("nightly")
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.raw == other.raw {
+            return Some(Ordering::Equal);
+        }
+
+        if self.is_unordered() || other.is_unordered() {
+            return None;
+        }
+
+        // Versions that don't start with a number (i.e commit hashes) can only be compared for equality
+        if let (Some(Token::Text(a)), Some(Token::Text(b))) =
+            (self.release.first(), other.release.first())
+        {
+            if a != b {
+                return None;
+            }
+        }
+
+        let pre_release = match (&self.pre_release, &other.pre_release) {
+            (Some(a), Some(b)) => cmp_tokens(a, b),
+            // A release is newer than any of its pre-releases
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+        Some(
+            cmp_tokens(&self.release, &other.release)
+                .then(pre_release)
+                .then_with(|| cmp_tokens(&self.build, &other.build)),
+        )
+    }
+}
+
+/// Compare tokens pairwise, treating missing numbers as zero, so that `1.2` and `1.2.0` are equal
+fn cmp_tokens(a: &[Token], b: &[Token]) -> Ordering {
+    let len = a.len().max(b.len());
+
+    for i in 0..len {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(Token::Number(n)), None) => n.cmp(&0),
+            (None, Some(Token::Number(n))) => 0.cmp(n),
+            (Some(Token::Text(_)), None) => Ordering::Greater,
+            (None, Some(Token::Text(_))) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Labels that mark a pre-release, even without a dash before them, i.e `3.0.0rc1` or `2.0.0.alpha`
+const PRE_RELEASE_LABELS: &[&str] = &["a", "alpha", "b", "beta", "pre", "preview", "rc"];
+
+/// Find the start of a pre-release label at the end of a version, that isn't preceded by a dash
+///
+/// The label must come after a number, and be followed by nothing but numbers and separators,
+/// so that text in the middle of a version (i.e `2.43.0.windows.1`) is still part of the release
+fn trailing_label_start(version: &str) -> Option<usize> {
+    let mut chars = version.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !c.is_alphabetic() {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some((i, c)) = chars.next_if(|(_, c)| c.is_alphabetic()) {
+            end = i + c.len_utf8();
+        }
+
+        let label = &version[start..end];
+        let after_number = version[..start].chars().any(|c| c.is_ascii_digit());
+        let trailing = version[end..]
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '_' | '-'));
+
+        if PRE_RELEASE_LABELS.contains(&label) && after_number && trailing {
+            return Some(start);
+        }
+    }
+
+    None
+}
+
+/// Tokenize a pre-release, expanding the short `a` and `b` labels so they order with `alpha` and `beta`
+fn pre_release_tokens(pre_release: &str) -> Vec<Token> {
+    let mut tokens = tokenize(pre_release);
+
+    if let Some(Token::Text(label)) = tokens.first_mut() {
+        match label.as_str() {
+            "a" => *label = "alpha".to_string(),
+            "b" => *label = "beta".to_string(),
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Split a version into runs of digits and runs of letters, dropping separators
+fn tokenize(version: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = version.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut digits = String::new();
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                digits.push(c);
+            }
+
+            match digits.parse() {
+                Ok(number) => tokens.push(Token::Number(number)),
+                // Numbers too large to fit are compared as text
+                Err(_) => tokens.push(Token::Text(digits)),
+            }
+        } else if c.is_alphabetic() {
+            let mut text = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                text.push(c);
+            }
+
+            tokens.push(Token::Text(text));
+        } else {
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmp(a: &str, b: &str) -> Option<Ordering> {
+        Version::new(a).partial_cmp(&Version::new(b))
+    }
+
+    #[test]
+    fn test_release_ordering() {
+        assert_eq!(cmp("1.10.0", "1.9.0"), Some(Ordering::Greater));
+        assert_eq!(cmp("1.2", "1.2.0"), Some(Ordering::Equal));
+        assert_eq!(
+            cmp("2.43.0.windows.1", "2.43.0.windows.2"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(cmp("1.1.1w", "1.1.1"), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_pre_release_ordering() {
+        assert_eq!(cmp("1.2.3-beta.4", "1.2.3"), Some(Ordering::Less));
+        assert_eq!(cmp("1.2.3-beta.4", "1.2.3-beta.10"), Some(Ordering::Less));
+        assert_eq!(cmp("1.2.3-rc.1", "1.2.3-beta.2"), Some(Ordering::Greater));
+        assert_eq!(cmp("1.2.4-alpha", "1.2.3"), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_pre_release_without_dash() {
+        assert_eq!(cmp("3.0.0rc1", "3.0.0"), Some(Ordering::Less));
+        assert_eq!(cmp("1.2.3beta4", "1.2.3"), Some(Ordering::Less));
+        assert_eq!(cmp("2.0.0.alpha", "2.0.0"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0b2", "1.0"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0a1", "1.0b1"), Some(Ordering::Less));
+        assert_eq!(cmp("1.2.3beta4", "1.2.3-beta.4"), Some(Ordering::Equal));
+        assert_eq!(cmp("3.0.0rc2", "3.0.0rc1"), Some(Ordering::Greater));
+        assert_eq!(cmp("3.0.1rc1", "3.0.0"), Some(Ordering::Greater));
+        assert_eq!(
+            Change::between("3.0.0", "3.0.0rc1"),
+            Some(Change::Downgrade)
+        );
+    }
+
+    #[test]
+    fn test_dates_and_builds() {
+        assert_eq!(cmp("2024-01-15", "2023-12-31"), Some(Ordering::Greater));
+        assert_eq!(cmp("20240115", "20231231"), Some(Ordering::Greater));
+        assert_eq!(
+            cmp("1.2.3+20240115", "1.2.3+20231231"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(cmp("1.2.4+1", "1.2.3+2"), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_unordered() {
+        assert_eq!(cmp("nightly", "1.0.0"), None);
+        assert_eq!(cmp("nightly-20240115", "nightly-20240116"), None);
+        assert_eq!(cmp("nightly", "nightly"), Some(Ordering::Equal));
+        assert_eq!(cmp("abc1234", "def5678"), None);
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(Change::between("1.0.0", "1.0.1"), Some(Change::Upgrade));
+        assert_eq!(Change::between("1.0.1", "1.0.0"), Some(Change::Downgrade));
+        assert_eq!(
+            Change::between("nightly", "nightly-2"),
+            Some(Change::Unknown)
+        );
+        assert_eq!(Change::between("1.0", "1.0.0"), None);
+    }
+}